@@ -0,0 +1,223 @@
+mod openai;
+mod registry;
+mod validate;
+mod vertex;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::config::{Config, CustomToolDef};
+use openai::OpenAiProvider;
+pub use registry::{render_command, ToolRegistry};
+pub use validate::validate_tool_args;
+use vertex::VertexAiProvider;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub tool_type: String,
+    pub function: FunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    pub arguments: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: Option<String>,
+    pub tool_calls: Option<Vec<ToolCall>>,
+    pub tool_call_id: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatResponse {
+    pub choices: Vec<Choice>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Choice {
+    pub message: ChatMessage,
+}
+
+/// Backend-specific implementation of the chat protocol. Every provider translates its own
+/// wire format to/from `ChatMessage` so the rest of aish stays provider-agnostic.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// `tools` is the full advertised list — this provider's own `tools()` plus any
+    /// user-defined tools from the registry — so the model can actually call them.
+    async fn chat(&self, messages: Vec<ChatMessage>, tools: &[serde_json::Value]) -> Result<ChatMessage>;
+    fn tools(&self) -> Vec<serde_json::Value>;
+    async fn test_auth(&self) -> Result<()>;
+
+    /// Stream a chat completion, invoking `on_delta` for each content chunk as it arrives.
+    /// Providers without native streaming support can fall back to a single `chat` call.
+    async fn chat_stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: &[serde_json::Value],
+        on_delta: &mut dyn FnMut(&str),
+    ) -> Result<ChatMessage> {
+        let response = self.chat(messages, tools).await?;
+        if let Some(content) = &response.content {
+            on_delta(content);
+        }
+        Ok(response)
+    }
+}
+
+/// The five operations every provider exposes to the model, shared so each backend's
+/// `tools()` implementation doesn't have to restate them.
+pub(super) fn builtin_tools() -> Vec<serde_json::Value> {
+    vec![
+        json!({
+            "type": "function",
+            "function": {
+                "name": "execute_shell",
+                "description": "Execute a shell command and return its output",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "command": {
+                            "type": "string",
+                            "description": "The shell command to execute"
+                        }
+                    },
+                    "required": ["command"]
+                }
+            }
+        }),
+        json!({
+            "type": "function",
+            "function": {
+                "name": "fs_readfile",
+                "description": "Read the contents of a file",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Relative path to the file"
+                        }
+                    },
+                    "required": ["path"]
+                }
+            }
+        }),
+        json!({
+            "type": "function",
+            "function": {
+                "name": "fs_writefile",
+                "description": "Write content to a file",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Relative path to the file"
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "Content to write"
+                        }
+                    },
+                    "required": ["path", "content"]
+                }
+            }
+        }),
+        json!({
+            "type": "function",
+            "function": {
+                "name": "fs_makedir",
+                "description": "Create a directory",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Relative path to the directory"
+                        }
+                    },
+                    "required": ["path"]
+                }
+            }
+        }),
+        json!({
+            "type": "function",
+            "function": {
+                "name": "fs_listdir",
+                "description": "List contents of a directory",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Relative path to the directory"
+                        }
+                    },
+                    "required": ["path"]
+                }
+            }
+        }),
+    ]
+}
+
+/// Thin dispatcher that owns whichever `LlmProvider` the config selects, so call sites
+/// (`App`, the init wizard) don't need to know which backend is active.
+pub struct LlmClient {
+    provider: Box<dyn LlmProvider>,
+    registry: ToolRegistry,
+}
+
+impl LlmClient {
+    pub fn new(config: &Config, api_key: String) -> Self {
+        let provider: Box<dyn LlmProvider> = match config.llm.provider.to_lowercase().as_str() {
+            "vertex" | "vertexai" | "vertex-ai" | "gemini" => {
+                Box::new(VertexAiProvider::new(config))
+            }
+            _ => Box::new(OpenAiProvider::new(config, api_key)),
+        };
+
+        Self {
+            provider,
+            registry: ToolRegistry::from_config(config),
+        }
+    }
+
+    pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<ChatMessage> {
+        self.provider.chat(messages, &self.tools()).await
+    }
+
+    /// The tool definitions the active backend will advertise to the model: its built-ins
+    /// (and any provider-specific ones) plus every user-defined tool from config.
+    pub fn tools(&self) -> Vec<serde_json::Value> {
+        let mut tools = self.provider.tools();
+        tools.extend(self.registry.definitions());
+        tools
+    }
+
+    /// Resolve a returned tool-call name to a user-defined tool, if it isn't one of the
+    /// built-ins the dispatcher already handles.
+    pub fn custom_tool(&self, name: &str) -> Option<&CustomToolDef> {
+        self.registry.get(name)
+    }
+
+    /// See `LlmProvider::chat_stream`.
+    pub async fn chat_stream<F>(&self, messages: Vec<ChatMessage>, mut on_delta: F) -> Result<ChatMessage>
+    where
+        F: FnMut(&str),
+    {
+        self.provider.chat_stream(messages, &self.tools(), &mut on_delta).await
+    }
+
+    pub async fn test_api_key(&self) -> Result<()> {
+        self.provider.test_auth().await
+    }
+}
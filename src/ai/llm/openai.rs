@@ -0,0 +1,402 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use reqwest::StatusCode;
+use serde_json::json;
+
+use super::{builtin_tools, ChatMessage, ChatResponse, FunctionCall, LlmProvider, ToolCall};
+use crate::config::Config;
+
+/// Accumulator for a single tool call's deltas while an SSE stream is still in flight.
+#[derive(Debug, Default)]
+struct StreamingToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+const BASE_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 30_000;
+
+/// Exponential backoff with jitter: `base * 2^attempt`, capped, plus up to 50% random jitter
+/// so concurrent retries don't all land on the same instant.
+fn backoff_delay(attempt: u32) -> Duration {
+    let exp_ms = BASE_BACKOFF_MS.saturating_mul(1u64 << attempt.min(16)).min(MAX_BACKOFF_MS);
+    let jitter_ms = (rand::random::<f64>() * exp_ms as f64 * 0.5) as u64;
+    Duration::from_millis(exp_ms + jitter_ms)
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn extract_error_message(status: StatusCode, response_text: &str) -> String {
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(response_text) {
+        if let Some(message) = json.get("error").and_then(|e| e.get("message")).and_then(|m| m.as_str()) {
+            return format!("API error ({}): {}", status, message);
+        }
+    }
+    format!("API error ({}): {}", status, response_text)
+}
+
+pub struct OpenAiProvider {
+    client: reqwest::Client,
+    api_url: String,
+    api_key: String,
+    model: String,
+    max_tokens: u32,
+    max_retries: u32,
+    fallback_models: Vec<String>,
+}
+
+impl OpenAiProvider {
+    pub fn new(config: &Config, api_key: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_url: config.llm.api_url.clone(),
+            api_key,
+            model: config.llm.model.clone(),
+            max_tokens: config.llm.max_tokens,
+            max_retries: config.llm.max_retries,
+            fallback_models: config.llm.fallback_models.clone(),
+        }
+    }
+
+    /// Primary model followed by the configured fallbacks, tried in order.
+    fn candidate_models(&self) -> Vec<String> {
+        std::iter::once(self.model.clone())
+            .chain(self.fallback_models.iter().cloned())
+            .collect()
+    }
+
+    async fn request_once(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+    ) -> reqwest::Result<reqwest::Response> {
+        self.client
+            .post(format!("{}/chat/completions", self.api_url))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&json!({
+                "model": model,
+                "messages": messages,
+                "tools": tools,
+                "max_tokens": self.max_tokens,
+            }))
+            .send()
+            .await
+    }
+
+    async fn stream_request_once(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+    ) -> reqwest::Result<reqwest::Response> {
+        self.client
+            .post(format!("{}/chat/completions", self.api_url))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&json!({
+                "model": model,
+                "messages": messages,
+                "tools": tools,
+                "max_tokens": self.max_tokens,
+                "stream": true,
+            }))
+            .send()
+            .await
+    }
+
+    /// Send to a single model, retrying with exponential backoff on 429/5xx (honoring
+    /// `Retry-After` when present) up to `max_retries` times. Returns the last error seen.
+    async fn chat_on_model(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+    ) -> Result<ChatMessage, String> {
+        let mut last_error = String::new();
+
+        for attempt in 0..=self.max_retries {
+            let response = match self.request_once(model, messages, tools).await {
+                Ok(response) => response,
+                Err(e) => {
+                    last_error = e.to_string();
+                    if attempt < self.max_retries {
+                        tokio::time::sleep(backoff_delay(attempt)).await;
+                    }
+                    continue;
+                }
+            };
+
+            let status = response.status();
+            if status.is_success() {
+                let response_text = response.text().await.map_err(|e| e.to_string())?;
+                let chat_response: ChatResponse =
+                    serde_json::from_str(&response_text).map_err(|e| {
+                        format!(
+                            "Failed to parse API response: {}\nResponse body: {}",
+                            e, response_text
+                        )
+                    })?;
+                if chat_response.choices.is_empty() {
+                    return Err("API returned empty choices array".to_string());
+                }
+                return Ok(chat_response.choices[0].message.clone());
+            }
+
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok());
+            let response_text = response.text().await.unwrap_or_default();
+            last_error = extract_error_message(status, &response_text);
+
+            if !is_retryable(status) || attempt == self.max_retries {
+                break;
+            }
+
+            let delay = retry_after.map(Duration::from_secs).unwrap_or_else(|| backoff_delay(attempt));
+            tokio::time::sleep(delay).await;
+        }
+
+        Err(last_error)
+    }
+
+    /// Same retry/backoff policy as `chat_on_model`, applied to the streaming request: a
+    /// 429/5xx before the SSE body starts arriving is retried with backoff (honoring
+    /// `Retry-After`); once the stream itself is consuming, errors propagate immediately rather
+    /// than replaying already-emitted `on_delta` output.
+    async fn chat_stream_on_model(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        on_delta: &mut dyn FnMut(&str),
+    ) -> Result<ChatMessage, String> {
+        let mut last_error = String::new();
+
+        for attempt in 0..=self.max_retries {
+            let response = match self.stream_request_once(model, messages, tools).await {
+                Ok(response) => response,
+                Err(e) => {
+                    last_error = e.to_string();
+                    if attempt < self.max_retries {
+                        tokio::time::sleep(backoff_delay(attempt)).await;
+                    }
+                    continue;
+                }
+            };
+
+            let status = response.status();
+            if status.is_success() {
+                return consume_sse_stream(response, on_delta).await.map_err(|e| e.to_string());
+            }
+
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok());
+            let response_text = response.text().await.unwrap_or_default();
+            last_error = extract_error_message(status, &response_text);
+
+            if !is_retryable(status) || attempt == self.max_retries {
+                break;
+            }
+
+            let delay = retry_after.map(Duration::from_secs).unwrap_or_else(|| backoff_delay(attempt));
+            tokio::time::sleep(delay).await;
+        }
+
+        Err(last_error)
+    }
+}
+
+/// Read a successful chat-completions SSE response to the end, invoking `on_delta` for each
+/// content chunk and reassembling any streamed tool calls.
+async fn consume_sse_stream(
+    response: reqwest::Response,
+    on_delta: &mut dyn FnMut(&str),
+) -> Result<ChatMessage> {
+    let mut role = "assistant".to_string();
+    let mut content = String::new();
+    let mut tool_calls: Vec<StreamingToolCall> = Vec::new();
+    let mut buffer = String::new();
+
+    let mut stream = response.bytes_stream();
+    'outer: while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(newline_pos) = buffer.find('\n') {
+            let line = buffer[..newline_pos].trim_end_matches('\r').to_string();
+            buffer.drain(..=newline_pos);
+
+            let Some(data) = line.strip_prefix("data: ") else {
+                continue;
+            };
+
+            if data == "[DONE]" {
+                break 'outer;
+            }
+
+            let event: serde_json::Value = serde_json::from_str(data)?;
+            let delta = &event["choices"][0]["delta"];
+
+            if let Some(r) = delta.get("role").and_then(|r| r.as_str()) {
+                role = r.to_string();
+            }
+
+            if let Some(piece) = delta.get("content").and_then(|c| c.as_str()) {
+                content.push_str(piece);
+                on_delta(piece);
+            }
+
+            if let Some(deltas) = delta.get("tool_calls").and_then(|t| t.as_array()) {
+                for tc_delta in deltas {
+                    let index = tc_delta.get("index").and_then(|i| i.as_u64()).unwrap_or(0) as usize;
+                    while tool_calls.len() <= index {
+                        tool_calls.push(StreamingToolCall::default());
+                    }
+                    let entry = &mut tool_calls[index];
+
+                    if let Some(id) = tc_delta.get("id").and_then(|i| i.as_str()) {
+                        entry.id = id.to_string();
+                    }
+                    if let Some(function) = tc_delta.get("function") {
+                        if let Some(name) = function.get("name").and_then(|n| n.as_str()) {
+                            entry.name.push_str(name);
+                        }
+                        if let Some(args) = function.get("arguments").and_then(|a| a.as_str()) {
+                            entry.arguments.push_str(args);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let finalized_tool_calls = if tool_calls.is_empty() {
+        None
+    } else {
+        let mut finalized = Vec::with_capacity(tool_calls.len());
+        for tc in tool_calls {
+            // A tool invoked with no arguments never receives a `function.arguments` delta, so
+            // the accumulator stays empty; treat that as `{}` rather than rejecting a valid
+            // zero-argument call for not being valid JSON.
+            let arguments = if tc.arguments.is_empty() { "{}".to_string() } else { tc.arguments };
+            if serde_json::from_str::<serde_json::Value>(&arguments).is_err() {
+                anyhow::bail!(
+                    "Tool call '{}' is invalid: arguments must be valid JSON",
+                    tc.name
+                );
+            }
+            finalized.push(ToolCall {
+                id: tc.id,
+                tool_type: "function".to_string(),
+                function: FunctionCall {
+                    name: tc.name,
+                    arguments,
+                },
+            });
+        }
+        Some(finalized)
+    };
+
+    Ok(ChatMessage {
+        role,
+        content: if content.is_empty() { None } else { Some(content) },
+        tool_calls: finalized_tool_calls,
+        tool_call_id: None,
+        name: None,
+    })
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    async fn chat(&self, messages: Vec<ChatMessage>, tools: &[serde_json::Value]) -> Result<ChatMessage> {
+        let mut attempt_history = Vec::new();
+
+        for model in self.candidate_models() {
+            match self.chat_on_model(&model, &messages, tools).await {
+                Ok(message) => return Ok(message),
+                Err(e) => attempt_history.push(format!("[{}] {}", model, e)),
+            }
+        }
+
+        anyhow::bail!(
+            "Exhausted {} model(s) after retries:\n{}",
+            attempt_history.len(),
+            attempt_history.join("\n")
+        )
+    }
+
+    fn tools(&self) -> Vec<serde_json::Value> {
+        builtin_tools()
+    }
+
+    async fn test_auth(&self) -> Result<()> {
+        // Make a minimal API call to test authentication
+        let test_message = ChatMessage {
+            role: "user".to_string(),
+            content: Some("test".to_string()),
+            tool_calls: None,
+            tool_call_id: None,
+            name: None,
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/chat/completions", self.api_url))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&json!({
+                "model": self.model,
+                "messages": [test_message],
+                "max_tokens": 5,
+            }))
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("API returned error: {}", error_text)
+        }
+    }
+
+    /// Stream a chat completion over SSE, invoking `on_delta` for each content chunk as it
+    /// arrives. Candidate models (primary then `fallback_models`) are tried in order, each
+    /// with the same retry/backoff policy as `chat`, so a 429/5xx no longer aborts the
+    /// session outright. Tool calls are reassembled from their fragmented deltas and only
+    /// validated as JSON once the stream settles on a final `index`.
+    async fn chat_stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: &[serde_json::Value],
+        on_delta: &mut dyn FnMut(&str),
+    ) -> Result<ChatMessage> {
+        let mut attempt_history = Vec::new();
+
+        for model in self.candidate_models() {
+            match self.chat_stream_on_model(&model, &messages, tools, &mut *on_delta).await {
+                Ok(message) => return Ok(message),
+                Err(e) => attempt_history.push(format!("[{}] {}", model, e)),
+            }
+        }
+
+        anyhow::bail!(
+            "Exhausted {} model(s) after retries:\n{}",
+            attempt_history.len(),
+            attempt_history.join("\n")
+        )
+    }
+}
@@ -0,0 +1,59 @@
+use serde_json::{json, Value};
+
+use crate::config::{Config, CustomToolDef};
+
+/// Extra tools declared in config — e.g. a `git_commit` or `kubectl_get` wrapper — that bind
+/// a JSON-Schema parameter set to a shell command template. Lets users extend what the model
+/// can invoke without recompiling aish.
+pub struct ToolRegistry {
+    tools: Vec<CustomToolDef>,
+}
+
+impl ToolRegistry {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            tools: config.custom_tools.clone(),
+        }
+    }
+
+    /// JSON Schema tool definitions in the same shape as the built-in tools, ready to be
+    /// merged into the `tools` array sent to the model.
+    pub fn definitions(&self) -> Vec<Value> {
+        self.tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.parameters,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Resolve a returned `ToolCall.function.name` to its template, if it names a
+    /// user-defined tool rather than one of the five built-ins.
+    pub fn get(&self, name: &str) -> Option<&CustomToolDef> {
+        self.tools.iter().find(|tool| tool.name == name)
+    }
+}
+
+/// Substitute each `{placeholder}` in a custom tool's command template with the matching
+/// argument, stringifying non-string JSON values.
+pub fn render_command(tool: &CustomToolDef, args: &Value) -> String {
+    let mut command = tool.command.clone();
+    if let Some(obj) = args.as_object() {
+        for (key, value) in obj {
+            let placeholder = format!("{{{}}}", key);
+            let replacement = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            command = command.replace(&placeholder, &replacement);
+        }
+    }
+    command
+}
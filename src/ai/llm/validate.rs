@@ -0,0 +1,51 @@
+use serde_json::Value;
+
+/// Check `args` against a tool's declared JSON-Schema `parameters` object: at minimum that
+/// it's an object, every `required` property is present, and present properties match their
+/// declared `"type"`. Returns a human-readable description of the first problem found.
+pub fn validate_tool_args(parameters: &Value, args: &Value) -> Result<(), String> {
+    let Some(args_obj) = args.as_object() else {
+        return Err("arguments must be a JSON object".to_string());
+    };
+
+    if let Some(required) = parameters.get("required").and_then(|r| r.as_array()) {
+        for field in required {
+            let Some(field) = field.as_str() else { continue };
+            if !args_obj.contains_key(field) {
+                return Err(format!("missing required property '{}'", field));
+            }
+        }
+    }
+
+    if let Some(properties) = parameters.get("properties").and_then(|p| p.as_object()) {
+        for (key, value) in args_obj {
+            let Some(spec) = properties.get(key) else {
+                continue;
+            };
+            let Some(expected_type) = spec.get("type").and_then(|t| t.as_str()) else {
+                continue;
+            };
+            if !matches_json_type(value, expected_type) {
+                return Err(format!(
+                    "property '{}' must be of type '{}'",
+                    key, expected_type
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn matches_json_type(value: &Value, expected_type: &str) -> bool {
+    match expected_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
@@ -0,0 +1,304 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use super::{ChatMessage, FunctionCall, LlmProvider, ToolCall};
+use crate::config::Config;
+
+const OAUTH_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+/// Refresh this many seconds before the token's real expiry to absorb request latency.
+const EXPIRY_SAFETY_MARGIN: u64 = 60;
+
+/// Application-default-credentials service account key, as written by `gcloud auth
+/// application-default login --impersonate-service-account` or downloaded from IAM.
+#[derive(Debug, Deserialize)]
+struct AdcServiceAccount {
+    client_email: String,
+    private_key: String,
+    #[serde(default = "default_token_uri")]
+    token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    "https://oauth2.googleapis.com/token".to_string()
+}
+
+#[derive(Debug, Serialize)]
+struct JwtClaims {
+    iss: String,
+    scope: String,
+    aud: String,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+pub struct VertexAiProvider {
+    client: reqwest::Client,
+    project_id: String,
+    location: String,
+    model: String,
+    adc_file: PathBuf,
+    max_tokens: u32,
+    token_cache: Mutex<Option<CachedToken>>,
+}
+
+impl VertexAiProvider {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            project_id: config.llm.project_id.clone().unwrap_or_default(),
+            location: config.llm.location.clone().unwrap_or_else(|| "us-central1".to_string()),
+            model: config.llm.model.clone(),
+            adc_file: config
+                .llm
+                .adc_file
+                .clone()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("application_default_credentials.json")),
+            max_tokens: config.llm.max_tokens,
+            token_cache: Mutex::new(None),
+        }
+    }
+
+    fn endpoint(&self) -> String {
+        format!(
+            "https://{location}-aiplatform.googleapis.com/v1/projects/{project}/locations/{location}/publishers/google/models/{model}:generateContent",
+            location = self.location,
+            project = self.project_id,
+            model = self.model,
+        )
+    }
+
+    async fn access_token(&self) -> Result<String> {
+        {
+            let cache = self.token_cache.lock().await;
+            if let Some(cached) = cache.as_ref() {
+                if cached.expires_at > Instant::now() {
+                    return Ok(cached.access_token.clone());
+                }
+            }
+        }
+
+        let creds_json = std::fs::read_to_string(&self.adc_file)
+            .with_context(|| format!("Failed to read ADC file: {:?}", self.adc_file))?;
+        let creds: AdcServiceAccount = serde_json::from_str(&creds_json)
+            .context("Failed to parse application-default-credentials JSON")?;
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let claims = JwtClaims {
+            iss: creds.client_email.clone(),
+            scope: OAUTH_SCOPE.to_string(),
+            aud: creds.token_uri.clone(),
+            iat: now,
+            exp: now + 3600,
+        };
+
+        let encoding_key = EncodingKey::from_rsa_pem(creds.private_key.as_bytes())
+            .context("Failed to parse service account private key")?;
+        let assertion = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &encoding_key)
+            .context("Failed to sign OAuth JWT assertion")?;
+
+        let response = self
+            .client
+            .post(&creds.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to obtain Vertex AI access token: {}", body);
+        }
+
+        let token: TokenResponse = response.json().await?;
+        let expires_at = Instant::now()
+            + Duration::from_secs(token.expires_in.saturating_sub(EXPIRY_SAFETY_MARGIN));
+
+        let mut cache = self.token_cache.lock().await;
+        *cache = Some(CachedToken {
+            access_token: token.access_token.clone(),
+            expires_at,
+        });
+
+        Ok(token.access_token)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for VertexAiProvider {
+    async fn chat(&self, messages: Vec<ChatMessage>, tools: &[serde_json::Value]) -> Result<ChatMessage> {
+        let access_token = self.access_token().await?;
+        let (system_instruction, contents) = to_gemini_contents(&messages);
+        let function_declarations: Vec<serde_json::Value> =
+            tools.iter().filter_map(|tool| tool.get("function").cloned()).collect();
+
+        let mut body = json!({
+            "contents": contents,
+            "tools": [{ "functionDeclarations": function_declarations }],
+            "generationConfig": { "maxOutputTokens": self.max_tokens },
+        });
+        if let Some(system_instruction) = system_instruction {
+            body["systemInstruction"] = system_instruction;
+        }
+
+        let response = self
+            .client
+            .post(self.endpoint())
+            .bearer_auth(access_token)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let response_text = response.text().await?;
+
+        if !status.is_success() {
+            anyhow::bail!("API error ({}): {}", status, response_text);
+        }
+
+        let response_json: serde_json::Value = serde_json::from_str(&response_text).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to parse API response: {}\nResponse body: {}",
+                e,
+                response_text
+            )
+        })?;
+
+        from_gemini_response(&response_json)
+    }
+
+    fn tools(&self) -> Vec<serde_json::Value> {
+        super::builtin_tools()
+            .into_iter()
+            .filter_map(|tool| tool.get("function").cloned())
+            .collect()
+    }
+
+    async fn test_auth(&self) -> Result<()> {
+        self.access_token().await.map(|_| ())
+    }
+}
+
+/// Translate our OpenAI-shaped messages into Gemini's `contents` array, splitting off the
+/// system message (there's at most one, always first) into `systemInstruction`.
+fn to_gemini_contents(messages: &[ChatMessage]) -> (Option<serde_json::Value>, Vec<serde_json::Value>) {
+    let mut system_instruction = None;
+    let mut contents = Vec::new();
+
+    for message in messages {
+        match message.role.as_str() {
+            "system" => {
+                if let Some(content) = &message.content {
+                    system_instruction = Some(json!({ "parts": [{ "text": content }] }));
+                }
+            }
+            "tool" => {
+                contents.push(json!({
+                    "role": "function",
+                    "parts": [{
+                        "functionResponse": {
+                            "name": message.name.clone().unwrap_or_default(),
+                            "response": { "content": message.content.clone().unwrap_or_default() },
+                        }
+                    }],
+                }));
+            }
+            "assistant" => {
+                let mut parts = Vec::new();
+                if let Some(content) = &message.content {
+                    parts.push(json!({ "text": content }));
+                }
+                for tool_call in message.tool_calls.iter().flatten() {
+                    let args: serde_json::Value =
+                        serde_json::from_str(&tool_call.function.arguments).unwrap_or(json!({}));
+                    parts.push(json!({
+                        "functionCall": {
+                            "name": tool_call.function.name,
+                            "args": args,
+                        }
+                    }));
+                }
+                contents.push(json!({ "role": "model", "parts": parts }));
+            }
+            _ => {
+                contents.push(json!({
+                    "role": "user",
+                    "parts": [{ "text": message.content.clone().unwrap_or_default() }],
+                }));
+            }
+        }
+    }
+
+    (system_instruction, contents)
+}
+
+/// Translate a Gemini `generateContent` response back into our `ChatMessage` shape,
+/// turning any `functionCall` parts into the `tool_calls` the dispatch loop expects.
+fn from_gemini_response(response: &serde_json::Value) -> Result<ChatMessage> {
+    let candidate = response
+        .get("candidates")
+        .and_then(|c| c.get(0))
+        .context("API returned no candidates")?;
+    let parts = candidate
+        .get("content")
+        .and_then(|c| c.get("parts"))
+        .and_then(|p| p.as_array())
+        .context("API response missing content parts")?;
+
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+
+    for (index, part) in parts.iter().enumerate() {
+        if let Some(piece) = part.get("text").and_then(|t| t.as_str()) {
+            text.push_str(piece);
+        }
+        if let Some(function_call) = part.get("functionCall") {
+            let name = function_call
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or_default()
+                .to_string();
+            let args = function_call.get("args").cloned().unwrap_or(json!({}));
+            tool_calls.push(ToolCall {
+                id: format!("vertex-call-{index}"),
+                tool_type: "function".to_string(),
+                function: FunctionCall {
+                    name,
+                    arguments: args.to_string(),
+                },
+            });
+        }
+    }
+
+    Ok(ChatMessage {
+        role: "assistant".to_string(),
+        content: if text.is_empty() { None } else { Some(text) },
+        tool_calls: if tool_calls.is_empty() { None } else { Some(tool_calls) },
+        tool_call_id: None,
+        name: None,
+    })
+}
@@ -0,0 +1,124 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::WorkspaceContext;
+
+/// How long a cached `WorkspaceContext` stays valid even if the fingerprint still matches.
+const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Manifest files (relative to the workspace root) whose mtime/size make up the fingerprint.
+/// Mirrors the files `detect` actually reads, so any change that would change detection
+/// results invalidates the cache.
+const FINGERPRINT_FILES: &[&str] = &[
+    "package.json",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "Cargo.toml",
+    "Cargo.lock",
+    "pyproject.toml",
+    "requirements.txt",
+    "poetry.lock",
+    "Pipfile",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "go.mod",
+    "go.sum",
+    "composer.json",
+    "Gemfile",
+    "Gemfile.lock",
+    ".git/HEAD",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    fingerprint: u64,
+    expires_at: u64,
+    context: WorkspaceContext,
+}
+
+/// Hash the mtime/size of every manifest file in `FINGERPRINT_FILES` that exists under
+/// `workspace_dir` into a single value. A missing file still perturbs the hash (via its
+/// absence) so adding/removing a manifest invalidates the cache too.
+fn fingerprint(workspace_dir: &PathBuf) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for relative in FINGERPRINT_FILES {
+        match std::fs::metadata(workspace_dir.join(relative)) {
+            Ok(meta) => {
+                true.hash(&mut hasher);
+                meta.len().hash(&mut hasher);
+                if let Ok(modified) = meta.modified() {
+                    if let Ok(since_epoch) = modified.duration_since(UNIX_EPOCH) {
+                        since_epoch.as_secs().hash(&mut hasher);
+                    }
+                }
+            }
+            Err(_) => false.hash(&mut hasher),
+        }
+    }
+    hasher.finish()
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join(".aish").join("cache").join("workspace_context"))
+}
+
+fn cache_file_for(workspace_dir: &PathBuf) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    workspace_dir.hash(&mut hasher);
+    let key = hasher.finish();
+    Some(cache_dir()?.join(format!("{:016x}.json", key)))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Like `WorkspaceContext::detect`, but skips the re-scan (file reads, `git` shell-out, full
+/// recursive walk) when a non-expired cache entry already matches the workspace's fingerprint.
+pub fn detect_cached(workspace_dir: &PathBuf) -> WorkspaceContext {
+    let current_fingerprint = fingerprint(workspace_dir);
+
+    if let Some(cache_file) = cache_file_for(workspace_dir) {
+        if let Ok(raw) = std::fs::read_to_string(&cache_file) {
+            if let Ok(entry) = serde_json::from_str::<CacheEntry>(&raw) {
+                if entry.fingerprint == current_fingerprint && entry.expires_at > now_secs() {
+                    return entry.context;
+                }
+            }
+        }
+    }
+
+    let context = WorkspaceContext::detect(workspace_dir);
+    store(workspace_dir, current_fingerprint, &context);
+    context
+}
+
+fn store(workspace_dir: &PathBuf, fingerprint: u64, context: &WorkspaceContext) {
+    let Some(cache_file) = cache_file_for(workspace_dir) else {
+        return;
+    };
+    let Some(parent) = cache_file.parent() else {
+        return;
+    };
+    if std::fs::create_dir_all(parent).is_err() {
+        return;
+    }
+    let entry = CacheEntry {
+        fingerprint,
+        expires_at: now_secs() + CACHE_TTL_SECS,
+        context: context.clone(),
+    };
+    if let Ok(serialized) = serde_json::to_string(&entry) {
+        let _ = std::fs::write(cache_file, serialized);
+    }
+}
@@ -0,0 +1,92 @@
+/// How a vendor's table entry decides whether the current build is a pull/merge request.
+enum PrDetector {
+    /// The build is a PR iff this variable is set at all.
+    VarPresent(&'static str),
+    /// The build is a PR iff this variable is set and not equal to the given value
+    /// (some vendors set it to `"false"` on non-PR builds instead of leaving it unset).
+    NotEqual(&'static str, &'static str),
+    /// The build is a PR iff any of these variables is set.
+    AnyOf(&'static [&'static str]),
+    /// This vendor doesn't expose a PR signal.
+    None,
+}
+
+struct CiVendor {
+    name: &'static str,
+    /// Every one of these env vars must be present for this vendor to match.
+    env: &'static [&'static str],
+    pr: PrDetector,
+}
+
+/// Env-var fingerprints for CI vendors not already (or not reliably) identifiable from files
+/// on disk, plus the mainstream ones `detect_ci_cd` already covers via config files — runtime
+/// detection catches them even when aish runs inside the CI job itself rather than against a
+/// checkout of its config.
+const CI_VENDORS: &[CiVendor] = &[
+    CiVendor { name: "github-actions", env: &["GITHUB_ACTIONS"], pr: PrDetector::VarPresent("GITHUB_HEAD_REF") },
+    CiVendor { name: "gitlab-ci", env: &["GITLAB_CI"], pr: PrDetector::VarPresent("CI_MERGE_REQUEST_ID") },
+    CiVendor { name: "circleci", env: &["CIRCLECI"], pr: PrDetector::VarPresent("CIRCLE_PULL_REQUEST") },
+    CiVendor { name: "travis-ci", env: &["TRAVIS"], pr: PrDetector::NotEqual("TRAVIS_PULL_REQUEST", "false") },
+    CiVendor { name: "jenkins", env: &["JENKINS_URL"], pr: PrDetector::VarPresent("CHANGE_ID") },
+    CiVendor {
+        name: "azure-pipelines",
+        env: &["SYSTEM_TEAMFOUNDATIONCOLLECTIONURI"],
+        pr: PrDetector::VarPresent("SYSTEM_PULLREQUEST_PULLREQUESTID"),
+    },
+    CiVendor { name: "bitbucket", env: &["BITBUCKET_COMMIT"], pr: PrDetector::VarPresent("BITBUCKET_PR_ID") },
+    CiVendor {
+        name: "appveyor",
+        env: &["APPVEYOR"],
+        pr: PrDetector::VarPresent("APPVEYOR_PULL_REQUEST_NUMBER"),
+    },
+    CiVendor { name: "bamboo", env: &["bamboo_planKey"], pr: PrDetector::None },
+    CiVendor { name: "bitrise", env: &["BITRISE_IO"], pr: PrDetector::VarPresent("BITRISE_PULL_REQUEST") },
+    CiVendor {
+        name: "buddy",
+        env: &["BUDDY_WORKSPACE_ID"],
+        pr: PrDetector::VarPresent("BUDDY_EXECUTION_PULL_REQUEST_ID"),
+    },
+];
+
+/// Generic env vars that indicate *some* CI is running even when no vendor-specific table
+/// entry matches.
+const GENERIC_CI_VARS: &[&str] = &["CI", "CONTINUOUS_INTEGRATION", "BUILD_NUMBER", "RUN_ID"];
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct CiEnvironment {
+    pub is_ci: bool,
+    pub is_pr: bool,
+    pub vendor: Option<String>,
+}
+
+fn env_set(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| !v.is_empty())
+}
+
+fn matches_pr(detector: &PrDetector) -> bool {
+    match detector {
+        PrDetector::VarPresent(var) => env_set(var),
+        PrDetector::NotEqual(var, value) => {
+            std::env::var(var).is_ok_and(|actual| actual != *value)
+        }
+        PrDetector::AnyOf(vars) => vars.iter().any(|var| env_set(var)),
+        PrDetector::None => false,
+    }
+}
+
+/// Identify the CI provider (and whether this is a PR build) purely from environment
+/// variables, so it works even when aish runs inside the CI job itself.
+pub fn detect_ci_environment() -> CiEnvironment {
+    for vendor in CI_VENDORS {
+        if vendor.env.iter().all(|var| env_set(var)) {
+            return CiEnvironment {
+                is_ci: true,
+                is_pr: matches_pr(&vendor.pr),
+                vendor: Some(vendor.name.to_string()),
+            };
+        }
+    }
+
+    let is_ci = GENERIC_CI_VARS.iter().any(|var| env_set(var));
+    CiEnvironment { is_ci, is_pr: false, vendor: None }
+}
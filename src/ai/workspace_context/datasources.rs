@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Where a newer version of a dependency could be fetched from, plus whether the current
+/// reference is already pinned to an immutable target (a digest or commit SHA) rather than a
+/// floating tag/branch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyUpdateEntry {
+    pub manager: String,
+    pub datasource: String,
+    pub dep_name: String,
+    pub current_version: String,
+    pub pinned: bool,
+}
+
+/// Walk every manifest this module knows how to read and emit one `DependencyUpdateEntry` per
+/// dependency, tagged with where an updated version would come from.
+pub fn detect_update_datasources(workspace_dir: &PathBuf) -> Vec<DependencyUpdateEntry> {
+    let mut entries = Vec::new();
+    entries.extend(npm_entries(workspace_dir));
+    entries.extend(pip_entries(workspace_dir));
+    entries.extend(cargo_entries(workspace_dir));
+    entries.extend(go_entries(workspace_dir));
+    entries.extend(docker_entries(workspace_dir));
+    entries.extend(github_actions_entries(workspace_dir));
+    entries
+}
+
+fn npm_entries(workspace_dir: &PathBuf) -> Vec<DependencyUpdateEntry> {
+    let Ok(content) = std::fs::read_to_string(workspace_dir.join("package.json")) else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    for section in ["dependencies", "devDependencies"] {
+        if let Some(deps) = json.get(section).and_then(|d| d.as_object()) {
+            for (name, version) in deps {
+                entries.push(DependencyUpdateEntry {
+                    manager: "npm".to_string(),
+                    datasource: "npm".to_string(),
+                    dep_name: name.clone(),
+                    current_version: version.as_str().unwrap_or("").to_string(),
+                    pinned: false,
+                });
+            }
+        }
+    }
+    entries
+}
+
+fn pip_entries(workspace_dir: &PathBuf) -> Vec<DependencyUpdateEntry> {
+    let Ok(content) = std::fs::read_to_string(workspace_dir.join("requirements.txt")) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, version) = line.split_once("==")?;
+            Some(DependencyUpdateEntry {
+                manager: "pip".to_string(),
+                datasource: "pypi".to_string(),
+                dep_name: name.trim().to_string(),
+                current_version: version.trim().to_string(),
+                pinned: false,
+            })
+        })
+        .collect()
+}
+
+fn cargo_entries(workspace_dir: &PathBuf) -> Vec<DependencyUpdateEntry> {
+    let Ok(content) = std::fs::read_to_string(workspace_dir.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Ok(toml) = content.parse::<toml::Value>() else {
+        return Vec::new();
+    };
+    let Some(deps) = toml.get("dependencies").and_then(|d| d.as_table()) else {
+        return Vec::new();
+    };
+    deps.iter()
+        .map(|(name, spec)| DependencyUpdateEntry {
+            manager: "cargo".to_string(),
+            datasource: "crates.io".to_string(),
+            dep_name: name.clone(),
+            current_version: spec
+                .as_str()
+                .map(|s| s.to_string())
+                .or_else(|| spec.get("version").and_then(|v| v.as_str()).map(|s| s.to_string()))
+                .unwrap_or_default(),
+            pinned: false,
+        })
+        .collect()
+}
+
+fn go_entries(workspace_dir: &PathBuf) -> Vec<DependencyUpdateEntry> {
+    let Ok(content) = std::fs::read_to_string(workspace_dir.join("go.mod")) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("require (") && *line != ")" && !line.starts_with("module "))
+        .map(|line| line.strip_prefix("require ").unwrap_or(line))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let version = parts.next()?;
+            if name.is_empty() || !version.starts_with('v') {
+                return None;
+            }
+            Some(DependencyUpdateEntry {
+                manager: "go".to_string(),
+                datasource: "goproxy".to_string(),
+                dep_name: name.to_string(),
+                current_version: version.to_string(),
+                pinned: false,
+            })
+        })
+        .collect()
+}
+
+/// Is the part after `@` in an image/action reference an immutable pin (digest or full commit
+/// SHA) rather than a floating tag/branch?
+fn is_pinned_ref(reference_suffix: &str) -> bool {
+    reference_suffix.starts_with("sha256:")
+        || (reference_suffix.len() == 40 && reference_suffix.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn docker_entries(workspace_dir: &PathBuf) -> Vec<DependencyUpdateEntry> {
+    let mut entries = Vec::new();
+    let files = [
+        workspace_dir.join("Dockerfile"),
+        workspace_dir.join("docker-compose.yml"),
+        workspace_dir.join("docker-compose.yaml"),
+    ];
+    for file in files {
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        for line in content.lines() {
+            let trimmed = line.trim();
+            let image_ref = if let Some(rest) = trimmed.strip_prefix("FROM ") {
+                rest.split_whitespace().next()
+            } else if let Some(rest) = trimmed.strip_prefix("image:") {
+                Some(rest.trim())
+            } else {
+                None
+            };
+            let Some(image_ref) = image_ref else { continue };
+            let (name, reference) = match image_ref.rsplit_once('@') {
+                Some((name, digest)) => (name.to_string(), digest.to_string()),
+                None => match image_ref.rsplit_once(':') {
+                    Some((name, tag)) => (name.to_string(), tag.to_string()),
+                    None => (image_ref.to_string(), "latest".to_string()),
+                },
+            };
+            entries.push(DependencyUpdateEntry {
+                manager: "docker".to_string(),
+                datasource: "docker".to_string(),
+                dep_name: name,
+                current_version: reference.clone(),
+                pinned: is_pinned_ref(&reference),
+            });
+        }
+    }
+    entries
+}
+
+fn github_actions_entries(workspace_dir: &PathBuf) -> Vec<DependencyUpdateEntry> {
+    let workflows_dir = workspace_dir.join(".github").join("workflows");
+    let Ok(files) = std::fs::read_dir(&workflows_dir) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    for file in files.filter_map(Result::ok) {
+        let Ok(content) = std::fs::read_to_string(file.path()) else {
+            continue;
+        };
+        for line in content.lines() {
+            let Some(rest) = line.trim().strip_prefix("uses: ") else {
+                continue;
+            };
+            let rest = rest.trim_matches('"').trim_matches('\'');
+            let Some((name, reference)) = rest.rsplit_once('@') else {
+                continue;
+            };
+            entries.push(DependencyUpdateEntry {
+                manager: "github-actions".to_string(),
+                datasource: "github-actions".to_string(),
+                dep_name: name.to_string(),
+                current_version: reference.to_string(),
+                pinned: is_pinned_ref(reference),
+            });
+        }
+    }
+    entries
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// Host-level facts (as opposed to per-project ones) that matter for generating correct shell
+/// commands: `apt` vs `brew`, GNU vs BSD flag differences, timezone-aware scheduling, etc.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuntimeEnvironment {
+    pub platform: String, // linux, macos, windows, bsd
+    pub distro: Option<String>,
+    pub distro_version: Option<String>,
+    pub uptime_seconds: Option<u64>,
+    pub timezone: Option<String>,
+    pub user: Option<String>,
+    pub shell: Option<String>,
+}
+
+pub fn detect_environment() -> RuntimeEnvironment {
+    RuntimeEnvironment {
+        platform: detect_platform_family(),
+        distro: detect_distro().map(|(name, _)| name),
+        distro_version: detect_distro().and_then(|(_, version)| version),
+        uptime_seconds: detect_uptime_seconds(),
+        timezone: detect_timezone(),
+        user: std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok(),
+        shell: std::env::var("SHELL").ok(),
+    }
+}
+
+fn detect_platform_family() -> String {
+    match std::env::consts::OS {
+        "macos" => "macos".to_string(),
+        "windows" => "windows".to_string(),
+        "freebsd" | "openbsd" | "netbsd" | "dragonfly" => "bsd".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Read `/etc/os-release`'s `NAME`/`VERSION_ID` (Linux only; `None` elsewhere).
+fn detect_distro() -> Option<(String, Option<String>)> {
+    let content = std::fs::read_to_string("/etc/os-release").ok()?;
+    let mut name = None;
+    let mut version = None;
+    for line in content.lines() {
+        if let Some(value) = line.strip_prefix("NAME=") {
+            name = Some(value.trim_matches('"').to_string());
+        }
+        if let Some(value) = line.strip_prefix("VERSION_ID=") {
+            version = Some(value.trim_matches('"').to_string());
+        }
+    }
+    name.map(|n| (n, version))
+}
+
+fn detect_uptime_seconds() -> Option<u64> {
+    let content = std::fs::read_to_string("/proc/uptime").ok()?;
+    content.split_whitespace().next()?.parse::<f64>().ok().map(|secs| secs as u64)
+}
+
+fn detect_timezone() -> Option<String> {
+    if let Ok(tz) = std::env::var("TZ") {
+        if !tz.is_empty() {
+            return Some(tz);
+        }
+    }
+    // /etc/localtime is typically a symlink into the tz database, e.g.
+    // /usr/share/zoneinfo/America/New_York
+    if let Ok(target) = std::fs::read_link("/etc/localtime") {
+        let target = target.to_string_lossy();
+        if let Some(idx) = target.find("zoneinfo/") {
+            return Some(target[idx + "zoneinfo/".len()..].to_string());
+        }
+    }
+    std::fs::read_to_string("/etc/timezone").ok().map(|s| s.trim().to_string())
+}
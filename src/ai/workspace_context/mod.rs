@@ -1,7 +1,38 @@
+mod cache;
+mod ci_env;
+mod datasources;
+mod environment;
+mod scan_engine;
+mod security_scan;
+mod versions;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use serde_json;
 use std::path::PathBuf;
 
-#[derive(Debug, Default)]
+pub use ci_env::CiEnvironment;
+use ci_env::detect_ci_environment;
+pub use datasources::{detect_update_datasources, DependencyUpdateEntry};
+pub use environment::{detect_environment, RuntimeEnvironment};
+use scan_engine::WorkspaceScan;
+pub use security_scan::{scan_security, AlertType, DependencyRecord, SecurityFinding, Severity};
+use versions::{
+    detect_maven_dependency_version, detect_node_dependency_version, detect_php_dependency_version,
+    detect_python_dependency_version, detect_ruby_gemfile_lock_version, detect_rust_lock_version,
+    detect_rust_package_version,
+};
+
+/// Selects how `WorkspaceContext` is rendered for a caller: human-readable `key: value` lines
+/// for the LLM prompt path, or structured JSON/YAML for programmatic consumers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Flags,
+    Json,
+    Yaml,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WorkspaceContext {
     // Version Control
     pub is_git_repo: bool,
@@ -28,6 +59,7 @@ pub struct WorkspaceContext {
 
     // Rust
     pub is_rust_project: bool,
+    pub rust_package_version: Option<String>,
 
     // Go
     pub is_go_project: bool,
@@ -42,10 +74,13 @@ pub struct WorkspaceContext {
 
     // .NET
     pub is_dotnet_project: bool,
+    pub dotnet_target_framework: Option<String>,
 
     // Web Frameworks
     pub web_framework: Option<String>, // react, vue, angular, nextjs, nuxt, svelte, remix, etc.
+    pub web_framework_version: Option<String>,
     pub backend_framework: Option<String>, // express, fastapi, django, flask, spring, etc.
+    pub backend_framework_version: Option<String>,
 
     // Build Tools
     pub build_tool: Option<String>, // webpack, vite, rollup, esbuild, etc.
@@ -61,6 +96,7 @@ pub struct WorkspaceContext {
 
     // CI/CD
     pub ci_cd_platform: Option<String>, // github-actions, gitlab-ci, jenkins, circleci, etc.
+    pub ci_environment: CiEnvironment,
 
     // Cloud Providers
     pub cloud_provider: Option<String>, // aws, gcp, azure, etc.
@@ -77,12 +113,48 @@ pub struct WorkspaceContext {
     // Linters & Formatters
     pub linter: Option<String>,    // eslint, pylint, rustfmt, etc.
     pub formatter: Option<String>, // prettier, black, gofmt, etc.
+
+    // Monorepos / nested services
+    pub sub_projects: Vec<WorkspaceContext>,
+
+    // Host machine
+    pub environment: RuntimeEnvironment,
 }
 
+/// Directory names the recursive walk never descends into, regardless of what's found there.
+const WALK_SKIP_DIRS: &[&str] = &["node_modules", ".git", "target", "vendor", "dist"];
+
+/// How many directory levels below the workspace root `detect` will walk looking for nested
+/// projects and glob-matched manifests (e.g. `*.csproj`).
+const DEFAULT_MAX_DEPTH: usize = 3;
+
 impl WorkspaceContext {
+    /// Detect the workspace at `workspace_dir`, plus up to `DEFAULT_MAX_DEPTH` levels of
+    /// nested sub-projects (a separate frontend/backend folder, a service in a monorepo, etc).
     pub fn detect(workspace_dir: &PathBuf) -> Self {
+        let mut context = Self::detect_local(workspace_dir);
+        context.sub_projects = find_sub_project_dirs(workspace_dir, DEFAULT_MAX_DEPTH)
+            .into_iter()
+            .map(|dir| Self::detect_local(&dir))
+            .collect();
+        context
+    }
+
+    /// Like `detect`, but reuses a cached result from a prior run when the workspace's manifest
+    /// fingerprint (mtimes/sizes of `package.json`, `Cargo.toml`, lockfiles, `.git/HEAD`, etc.)
+    /// hasn't changed, instead of re-reading every file and re-shelling out to `git`.
+    pub fn detect_cached(workspace_dir: &PathBuf) -> Self {
+        cache::detect_cached(workspace_dir)
+    }
+
+    /// Detect only `workspace_dir` itself, without descending into sub-projects. Used both as
+    /// the root pass of `detect` and to build each entry in `sub_projects`.
+    fn detect_local(workspace_dir: &PathBuf) -> Self {
         let mut context = WorkspaceContext::default();
 
+        // Detect host machine facts
+        context.environment = detect_environment();
+
         // Detect Git repository
         let git_dir = workspace_dir.join(".git");
         context.is_git_repo = git_dir.exists();
@@ -98,7 +170,14 @@ impl WorkspaceContext {
             context.is_typescript_project = workspace_dir.join("tsconfig.json").exists()
                 || workspace_dir.join("tsconfig.base.json").exists();
             context.web_framework = detect_web_framework(workspace_dir);
+            if let Some(ref framework) = context.web_framework {
+                context.web_framework_version = detect_node_dependency_version(workspace_dir, framework);
+            }
             context.backend_framework = detect_backend_framework(workspace_dir);
+            if let Some(ref framework) = context.backend_framework {
+                context.backend_framework_version =
+                    detect_node_dependency_version(workspace_dir, framework);
+            }
             context.build_tool = detect_build_tool(workspace_dir);
             context.testing_framework = detect_testing_framework(workspace_dir);
             context.linter = detect_linter(workspace_dir);
@@ -126,6 +205,10 @@ impl WorkspaceContext {
                 || workspace_dir.join("env").exists()
                 || workspace_dir.join(".env").exists();
             context.backend_framework = detect_python_backend_framework(workspace_dir);
+            if let Some(ref framework) = context.backend_framework {
+                context.backend_framework_version =
+                    detect_python_dependency_version(workspace_dir, framework);
+            }
             context.testing_framework = detect_python_testing_framework(workspace_dir);
         }
 
@@ -135,6 +218,13 @@ impl WorkspaceContext {
             context.java_project_manager = Some("maven".to_string());
             context.java_version = detect_java_version_from_pom(workspace_dir);
             context.backend_framework = detect_java_backend_framework(workspace_dir);
+            if context.backend_framework.as_deref() == Some("spring-boot") {
+                context.backend_framework_version = detect_maven_dependency_version(
+                    workspace_dir,
+                    "org.springframework.boot",
+                    "spring-boot-starter-parent",
+                );
+            }
         } else if workspace_dir.join("build.gradle").exists()
             || workspace_dir.join("build.gradle.kts").exists()
             || workspace_dir.join("settings.gradle").exists()
@@ -149,6 +239,13 @@ impl WorkspaceContext {
         // Detect Rust project
         if workspace_dir.join("Cargo.toml").exists() {
             context.is_rust_project = true;
+            context.rust_package_version = detect_rust_package_version(workspace_dir);
+            context.backend_framework = detect_rust_backend_framework(workspace_dir);
+            if let Some(ref framework) = context.backend_framework {
+                // Cargo.toml only carries a semver range; Cargo.lock has the crate version
+                // actually resolved, so prefer it for the version we report.
+                context.backend_framework_version = detect_rust_lock_version(workspace_dir, framework);
+            }
             context.linter = Some("clippy".to_string());
             context.formatter = Some("rustfmt".to_string());
         }
@@ -163,20 +260,36 @@ impl WorkspaceContext {
         // Detect PHP project
         if workspace_dir.join("composer.json").exists() {
             context.is_php_project = true;
+            context.backend_framework = detect_php_backend_framework(workspace_dir);
+            if let Some(ref framework) = context.backend_framework {
+                context.backend_framework_version =
+                    detect_php_dependency_version(workspace_dir, php_framework_package(framework));
+            }
         }
 
         // Detect Ruby project
         if workspace_dir.join("Gemfile").exists() || workspace_dir.join("Rakefile").exists() {
             context.is_ruby_project = true;
             context.ruby_version = detect_ruby_version(workspace_dir);
+            context.backend_framework = detect_ruby_backend_framework(workspace_dir);
+            if let Some(ref framework) = context.backend_framework {
+                context.backend_framework_version =
+                    detect_ruby_gemfile_lock_version(workspace_dir, framework);
+            }
         }
 
         // Detect .NET project
-        if workspace_dir.join("*.csproj").exists()
-            || workspace_dir.join("*.sln").exists()
-            || workspace_dir.join("*.fsproj").exists()
+        if dir_has_glob_match(workspace_dir, "*.csproj")
+            || dir_has_glob_match(workspace_dir, "*.sln")
+            || dir_has_glob_match(workspace_dir, "*.fsproj")
         {
             context.is_dotnet_project = true;
+            if let Some(project_file) = find_glob_match(workspace_dir, "*.csproj")
+                .or_else(|| find_glob_match(workspace_dir, "*.fsproj"))
+            {
+                context.dotnet_target_framework = detect_dotnet_target_framework(&project_file);
+                context.backend_framework = detect_dotnet_backend_framework(&project_file);
+            }
         }
 
         // Detect Docker
@@ -188,17 +301,26 @@ impl WorkspaceContext {
             || workspace_dir.join("docker-compose.yaml").exists();
         context.has_kubernetes = workspace_dir.join("k8s").exists()
             || workspace_dir.join("kubernetes").exists()
-            || workspace_dir.join("deployment.yaml").exists()
-            || workspace_dir.join("deployment.yml").exists();
+            || dir_has_glob_match(workspace_dir, "deployment*.yaml")
+            || dir_has_glob_match(workspace_dir, "deployment*.yml");
 
-        // Detect CI/CD
+        // Detect CI/CD (from files on disk, plus a runtime env-var check for jobs whose
+        // config lives outside this checkout)
         context.ci_cd_platform = detect_ci_cd(workspace_dir);
+        context.ci_environment = detect_ci_environment();
+        if context.ci_cd_platform.is_none() {
+            context.ci_cd_platform = context.ci_environment.vendor.clone();
+        }
+
+        // Single shared walk backing every detector below that needs real glob matching
+        // instead of a literal (and therefore always-false) `workspace_dir.join("*.tf")`.
+        let scan = WorkspaceScan::build(workspace_dir);
 
         // Detect Cloud Providers
-        context.cloud_provider = detect_cloud_provider(workspace_dir);
+        context.cloud_provider = detect_cloud_provider(workspace_dir, &scan);
 
         // Detect Infrastructure as Code
-        context.iac_tool = detect_iac_tool(workspace_dir);
+        context.iac_tool = detect_iac_tool(workspace_dir, &scan);
 
         // Detect Databases
         context.database = detect_database(workspace_dir);
@@ -218,6 +340,20 @@ impl WorkspaceContext {
     pub fn to_flags_string(&self) -> String {
         let mut flags = Vec::new();
 
+        flags.push(format!("platform: {}", self.environment.platform));
+        if let Some(ref distro) = self.environment.distro {
+            flags.push(format!("distro: {}", distro));
+        }
+        if let Some(ref version) = self.environment.distro_version {
+            flags.push(format!("distro_version: {}", version));
+        }
+        if let Some(ref timezone) = self.environment.timezone {
+            flags.push(format!("timezone: {}", timezone));
+        }
+        if let Some(ref shell) = self.environment.shell {
+            flags.push(format!("shell: {}", shell));
+        }
+
         if self.is_git_repo {
             flags.push("is_git_repo: true".to_string());
             if let Some(ref branch) = self.git_branch {
@@ -269,6 +405,9 @@ impl WorkspaceContext {
 
         if self.is_rust_project {
             flags.push("is_rust_project: true".to_string());
+            if let Some(ref version) = self.rust_package_version {
+                flags.push(format!("rust_package_version: {}", version));
+            }
         }
 
         if self.is_go_project {
@@ -291,14 +430,23 @@ impl WorkspaceContext {
 
         if self.is_dotnet_project {
             flags.push("is_dotnet_project: true".to_string());
+            if let Some(ref tfm) = self.dotnet_target_framework {
+                flags.push(format!("dotnet_target_framework: {}", tfm));
+            }
         }
 
         if let Some(ref framework) = self.web_framework {
             flags.push(format!("web_framework: {}", framework));
+            if let Some(ref version) = self.web_framework_version {
+                flags.push(format!("web_framework_version: {}", version));
+            }
         }
 
         if let Some(ref framework) = self.backend_framework {
             flags.push(format!("backend_framework: {}", framework));
+            if let Some(ref version) = self.backend_framework_version {
+                flags.push(format!("backend_framework_version: {}", version));
+            }
         }
 
         if let Some(ref tool) = self.build_tool {
@@ -325,6 +473,13 @@ impl WorkspaceContext {
             flags.push(format!("ci_cd_platform: {}", platform));
         }
 
+        if self.ci_environment.is_ci {
+            flags.push("is_ci: true".to_string());
+            if self.ci_environment.is_pr {
+                flags.push("is_pr: true".to_string());
+            }
+        }
+
         if let Some(ref provider) = self.cloud_provider {
             flags.push(format!("cloud_provider: {}", provider));
         }
@@ -353,8 +508,35 @@ impl WorkspaceContext {
             flags.push(format!("container_registry: {}", registry));
         }
 
+        if !self.sub_projects.is_empty() {
+            flags.push(format!("sub_projects: {}", self.sub_projects.len()));
+        }
+
         flags.join("\n")
     }
+
+    /// Render in the requested `OutputFormat`. `Flags` mirrors `to_flags_string`; `Json`/`Yaml`
+    /// fall back to an empty object on a serialization failure rather than propagating an error,
+    /// since this is typically interpolated straight into a prompt or printed best-effort.
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Flags => self.to_flags_string(),
+            OutputFormat::Json => self.to_json().unwrap_or_else(|_| "{}".to_string()),
+            OutputFormat::Yaml => self.to_yaml().unwrap_or_else(|_| "{}".to_string()),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn to_yaml(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
 }
 
 // Helper functions for detection
@@ -508,13 +690,11 @@ fn detect_python_backend_framework(workspace_dir: &PathBuf) -> Option<String> {
 }
 
 fn detect_java_backend_framework(workspace_dir: &PathBuf) -> Option<String> {
-    // Check for Spring Boot
-    if workspace_dir.join("pom.xml").exists() {
-        if let Ok(content) = std::fs::read_to_string(workspace_dir.join("pom.xml")) {
-            if content.contains("spring-boot") {
-                return Some("spring-boot".to_string());
-            }
-        }
+    // Check for Spring Boot via exact groupId, not a content.contains() substring match
+    if workspace_dir.join("pom.xml").exists()
+        && pom_has_group_id(workspace_dir, "org.springframework.boot")
+    {
+        return Some("spring-boot".to_string());
     }
     let gradle_file = if workspace_dir.join("build.gradle").exists() {
         Some(workspace_dir.join("build.gradle"))
@@ -533,6 +713,56 @@ fn detect_java_backend_framework(workspace_dir: &PathBuf) -> Option<String> {
     None
 }
 
+/// Identify a Rust web framework from `Cargo.toml`'s `[dependencies]` table.
+fn detect_rust_backend_framework(workspace_dir: &PathBuf) -> Option<String> {
+    let content = std::fs::read_to_string(workspace_dir.join("Cargo.toml")).ok()?;
+    let toml: toml::Value = toml::from_str(&content).ok()?;
+    let deps = toml.get("dependencies")?.as_table()?;
+    ["axum", "actix-web", "rocket", "warp"]
+        .into_iter()
+        .find(|framework| deps.contains_key(*framework))
+        .map(|framework| framework.to_string())
+}
+
+/// Identify a PHP web framework from `composer.json`'s `require` map.
+fn detect_php_backend_framework(workspace_dir: &PathBuf) -> Option<String> {
+    let content = std::fs::read_to_string(workspace_dir.join("composer.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let require = json.get("require")?.as_object()?;
+    if require.contains_key("laravel/framework") {
+        Some("laravel".to_string())
+    } else if require.contains_key("symfony/framework-bundle") {
+        Some("symfony".to_string())
+    } else if require.contains_key("cakephp/cakephp") {
+        Some("cakephp".to_string())
+    } else {
+        None
+    }
+}
+
+/// Map a detected PHP framework's display name to the `composer.json` `require` key that
+/// carries its version, mirroring `node_package_key` for Node frameworks.
+fn php_framework_package(framework: &str) -> &str {
+    match framework {
+        "laravel" => "laravel/framework",
+        "symfony" => "symfony/framework-bundle",
+        "cakephp" => "cakephp/cakephp",
+        other => other,
+    }
+}
+
+/// Identify a Ruby web framework from `Gemfile`'s gem declarations.
+fn detect_ruby_backend_framework(workspace_dir: &PathBuf) -> Option<String> {
+    let content = std::fs::read_to_string(workspace_dir.join("Gemfile")).ok()?;
+    if content.contains("rails") {
+        Some("rails".to_string())
+    } else if content.contains("sinatra") {
+        Some("sinatra".to_string())
+    } else {
+        None
+    }
+}
+
 fn detect_build_tool(workspace_dir: &PathBuf) -> Option<String> {
     if let Ok(content) = std::fs::read_to_string(workspace_dir.join("package.json")) {
         if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
@@ -735,23 +965,82 @@ fn detect_python_version(workspace_dir: &PathBuf) -> Option<String> {
     None
 }
 
-fn detect_java_version_from_pom(workspace_dir: &PathBuf) -> Option<String> {
-    if let Ok(content) = std::fs::read_to_string(workspace_dir.join("pom.xml")) {
-        // Look for <java.version> or <maven.compiler.source>
-        for line in content.lines() {
-            if line.contains("<java.version>") {
-                if let Some(version) = line.split('>').nth(1).and_then(|s| s.split('<').next()) {
-                    return Some(version.trim().to_string());
+/// Does `pom.xml` declare a dependency (or parent) with the given exact `groupId`? Walks the
+/// element tree with quick-xml rather than substring-matching the raw file, so a groupId
+/// mentioned only in a comment or an unrelated string doesn't false-positive.
+fn pom_has_group_id(workspace_dir: &PathBuf, group_id: &str) -> bool {
+    use quick_xml::events::Event;
+    use quick_xml::reader::Reader;
+
+    let Ok(content) = std::fs::read_to_string(workspace_dir.join("pom.xml")) else {
+        return false;
+    };
+    let mut reader = Reader::from_str(&content);
+    reader.config_mut().trim_text(true);
+
+    let mut path: Vec<String> = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => path.push(String::from_utf8_lossy(e.name().as_ref()).to_string()),
+            Ok(Event::Text(t)) => {
+                if path.last().map(|s| s.as_str()) == Some("groupId")
+                    && path.iter().any(|p| p == "dependency" || p == "parent")
+                    && t.unescape().unwrap_or_default().as_ref() == group_id
+                {
+                    return true;
                 }
             }
-            if line.contains("<maven.compiler.source>") {
-                if let Some(version) = line.split('>').nth(1).and_then(|s| s.split('<').next()) {
-                    return Some(version.trim().to_string());
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    false
+}
+
+/// Read `<properties><java.version>` / `<maven.compiler.source>` out of `pom.xml` via an
+/// XML element walk instead of a line-based substring search.
+fn detect_java_version_from_pom(workspace_dir: &PathBuf) -> Option<String> {
+    use quick_xml::events::Event;
+    use quick_xml::reader::Reader;
+
+    let content = std::fs::read_to_string(workspace_dir.join("pom.xml")).ok()?;
+    let mut reader = Reader::from_str(&content);
+    reader.config_mut().trim_text(true);
+
+    let mut path: Vec<String> = Vec::new();
+    let mut buf = Vec::new();
+    let mut java_version = None;
+    let mut compiler_source = None;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => path.push(String::from_utf8_lossy(e.name().as_ref()).to_string()),
+            Ok(Event::Text(t)) => {
+                let in_properties = path.len() >= 2 && path[path.len() - 2] == "properties";
+                if in_properties {
+                    let text = t.unescape().unwrap_or_default().to_string();
+                    match path.last().map(|s| s.as_str()) {
+                        Some("java.version") => java_version = Some(text),
+                        Some("maven.compiler.source") => compiler_source = Some(text),
+                        _ => {}
+                    }
                 }
             }
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
         }
+        buf.clear();
     }
-    None
+    java_version.or(compiler_source)
 }
 
 fn detect_java_version_from_gradle(workspace_dir: &PathBuf) -> Option<String> {
@@ -830,7 +1119,7 @@ fn detect_ci_cd(workspace_dir: &PathBuf) -> Option<String> {
     None
 }
 
-fn detect_cloud_provider(workspace_dir: &PathBuf) -> Option<String> {
+fn detect_cloud_provider(workspace_dir: &PathBuf, scan: &WorkspaceScan) -> Option<String> {
     // AWS
     if workspace_dir.join(".aws").exists()
         || workspace_dir.join("serverless.yml").exists()
@@ -853,13 +1142,21 @@ fn detect_cloud_provider(workspace_dir: &PathBuf) -> Option<String> {
     {
         return Some("azure".to_string());
     }
+    // Fall back to matching any Dockerfile that pulls from a cloud-specific base image
+    if scan.any_file_matching_contains("Dockerfile*", "amazonaws.com") {
+        return Some("aws".to_string());
+    }
+    if scan.any_file_matching_contains("Dockerfile*", "gcr.io") {
+        return Some("gcp".to_string());
+    }
     None
 }
 
-fn detect_iac_tool(workspace_dir: &PathBuf) -> Option<String> {
+fn detect_iac_tool(workspace_dir: &PathBuf, scan: &WorkspaceScan) -> Option<String> {
     if workspace_dir.join("terraform").exists()
-        || workspace_dir.join("*.tf").exists()
         || workspace_dir.join(".terraform").exists()
+        || scan.any_match("*.tf")
+        || scan.any_match("*.tfvars")
     {
         return Some("terraform".to_string());
     }
@@ -932,6 +1229,176 @@ fn detect_database(workspace_dir: &PathBuf) -> Option<String> {
     None
 }
 
+/// Manifest filenames whose presence marks a directory as a standalone project root worth
+/// reporting as a `sub_project`, distinct from the workspace root itself.
+const PROJECT_MANIFESTS: &[&str] = &[
+    "package.json",
+    "pyproject.toml",
+    "requirements.txt",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "Cargo.toml",
+    "go.mod",
+    "composer.json",
+    "Gemfile",
+];
+
+/// Does any entry of `dir` (non-recursive) match the glob `pattern`?
+fn dir_has_glob_match(dir: &PathBuf, pattern: &str) -> bool {
+    let Ok(glob_pattern) = glob::Pattern::new(pattern) else {
+        return false;
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries.filter_map(Result::ok).any(|entry| {
+        entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| glob_pattern.matches(name))
+    })
+}
+
+/// Return the path of the first entry in `dir` (non-recursive) matching the glob `pattern`.
+fn find_glob_match(dir: &PathBuf, pattern: &str) -> Option<PathBuf> {
+    let glob_pattern = glob::Pattern::new(pattern).ok()?;
+    let entries = std::fs::read_dir(dir).ok()?;
+    entries
+        .filter_map(Result::ok)
+        .find(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| glob_pattern.matches(name))
+        })
+        .map(|entry| entry.path())
+}
+
+/// Read `<TargetFramework>` (or the first entry of `<TargetFrameworks>`) from a `.csproj`/
+/// `.fsproj` file, e.g. `net8.0`.
+fn detect_dotnet_target_framework(project_file: &PathBuf) -> Option<String> {
+    use quick_xml::events::Event;
+    use quick_xml::reader::Reader;
+
+    let content = std::fs::read_to_string(project_file).ok()?;
+    let mut reader = Reader::from_str(&content);
+    reader.config_mut().trim_text(true);
+
+    let mut current_tag = String::new();
+    let mut buf = Vec::new();
+    let mut single = None;
+    let mut multiple = None;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                current_tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
+            }
+            Ok(Event::Text(t)) => {
+                let text = t.unescape().unwrap_or_default().to_string();
+                match current_tag.as_str() {
+                    "TargetFramework" => single = Some(text),
+                    "TargetFrameworks" => multiple = Some(text),
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    single.or_else(|| multiple.and_then(|m| m.split(';').next().map(|s| s.to_string())))
+}
+
+/// Identify ASP.NET Core / Blazor from `<PackageReference Include="...">` attributes rather
+/// than a raw substring search of the project file.
+fn detect_dotnet_backend_framework(project_file: &PathBuf) -> Option<String> {
+    use quick_xml::events::Event;
+    use quick_xml::reader::Reader;
+
+    let content = std::fs::read_to_string(project_file).ok()?;
+    let mut reader = Reader::from_str(&content);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut found_blazor = false;
+    let mut found_aspnetcore = false;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                if e.name().as_ref() == b"PackageReference" {
+                    if let Some(include) = e
+                        .attributes()
+                        .filter_map(Result::ok)
+                        .find(|a| a.key.as_ref() == b"Include")
+                    {
+                        let value = include.unescape_value().unwrap_or_default();
+                        if value.starts_with("Microsoft.AspNetCore.Components") {
+                            found_blazor = true;
+                        } else if value.starts_with("Microsoft.AspNetCore") {
+                            found_aspnetcore = true;
+                        }
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if found_blazor {
+        Some("blazor".to_string())
+    } else if found_aspnetcore {
+        Some("aspnetcore".to_string())
+    } else {
+        None
+    }
+}
+
+fn dir_has_project_manifest(dir: &PathBuf) -> bool {
+    PROJECT_MANIFESTS.iter().any(|manifest| dir.join(manifest).exists())
+        || dir_has_glob_match(dir, "*.csproj")
+        || dir_has_glob_match(dir, "*.sln")
+        || dir_has_glob_match(dir, "*.fsproj")
+}
+
+/// Recursively walk `root` (bounded to `max_depth` levels, skipping `WALK_SKIP_DIRS`) and
+/// return every subdirectory (excluding `root` itself) that carries its own project manifest.
+fn find_sub_project_dirs(root: &PathBuf, max_depth: usize) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    walk_for_sub_projects(root, max_depth, &mut found);
+    found
+}
+
+fn walk_for_sub_projects(dir: &PathBuf, depth_remaining: usize, found: &mut Vec<PathBuf>) {
+    if depth_remaining == 0 {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if WALK_SKIP_DIRS.contains(&name.as_ref()) {
+            continue;
+        }
+
+        if dir_has_project_manifest(&path) {
+            found.push(path.clone());
+        }
+
+        walk_for_sub_projects(&path, depth_remaining - 1, found);
+    }
+}
+
 fn detect_container_registry(workspace_dir: &PathBuf) -> Option<String> {
     // Check docker-compose for registry
     let docker_compose_content = std::fs::read_to_string(workspace_dir.join("docker-compose.yml"))
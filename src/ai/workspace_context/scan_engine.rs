@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use super::WALK_SKIP_DIRS;
+
+/// How many directory levels below the workspace root a `WorkspaceScan` walks.
+const SCAN_MAX_DEPTH: usize = 5;
+
+/// A single recursive walk of the workspace (bounded depth, `WALK_SKIP_DIRS` skipped,
+/// `.gitignore`-listed names skipped), shared by every detector that needs to ask "does any
+/// file match this glob" instead of each one re-walking the tree itself.
+pub struct WorkspaceScan {
+    files: Vec<PathBuf>,
+}
+
+impl WorkspaceScan {
+    pub fn build(workspace_dir: &PathBuf) -> Self {
+        let ignored = read_gitignore_patterns(workspace_dir);
+        let mut files = Vec::new();
+        walk(workspace_dir, SCAN_MAX_DEPTH, &ignored, &mut files);
+        Self { files }
+    }
+
+    /// Does any discovered file's name match the glob `pattern` (e.g. `*.tf`, `Dockerfile*`)?
+    pub fn any_match(&self, pattern: &str) -> bool {
+        let Ok(glob_pattern) = glob::Pattern::new(pattern) else {
+            return false;
+        };
+        self.files.iter().any(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| glob_pattern.matches(name))
+        })
+    }
+
+    /// Does any discovered file's content contain `needle`? Used sparingly (e.g. cloud
+    /// provider SDK imports) since it reads every matching file.
+    pub fn any_file_matching_contains(&self, pattern: &str, needle: &str) -> bool {
+        let Ok(glob_pattern) = glob::Pattern::new(pattern) else {
+            return false;
+        };
+        self.files
+            .iter()
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|name| glob_pattern.matches(name))
+            })
+            .any(|path| std::fs::read_to_string(path).is_ok_and(|content| content.contains(needle)))
+    }
+}
+
+/// A crude but effective `.gitignore` reader: every non-comment, non-blank line is treated as
+/// a glob matched against file *names* (not full relative paths), which covers the common case
+/// (`*.log`, `node_modules`, `dist`) without implementing full gitignore semantics.
+fn read_gitignore_patterns(workspace_dir: &PathBuf) -> Vec<String> {
+    std::fs::read_to_string(workspace_dir.join(".gitignore"))
+        .map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+                .map(|line| line.trim_end_matches('/').to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_ignored(name: &str, ignored: &[String]) -> bool {
+    ignored.iter().any(|pattern| {
+        glob::Pattern::new(pattern).is_ok_and(|p| p.matches(name)) || pattern == name
+    })
+}
+
+fn walk(dir: &PathBuf, depth_remaining: usize, ignored: &[String], out: &mut Vec<PathBuf>) {
+    if depth_remaining == 0 {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+
+        if path.is_dir() {
+            if WALK_SKIP_DIRS.contains(&name.as_ref()) || is_ignored(&name, ignored) {
+                continue;
+            }
+            walk(&path, depth_remaining - 1, ignored, out);
+        } else {
+            if is_ignored(&name, ignored) {
+                continue;
+            }
+            out.push(path);
+        }
+    }
+}
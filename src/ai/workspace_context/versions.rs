@@ -0,0 +1,225 @@
+use std::path::PathBuf;
+
+/// Strip a semver range prefix (`^`, `~`, `>=`, `<=`, `>`, `<`, `=`) so callers get a bare
+/// version number regardless of how the manifest pinned it.
+fn strip_semver_prefix(spec: &str) -> String {
+    spec.trim_start_matches(['^', '~', '>', '<', '='])
+        .trim()
+        .to_string()
+}
+
+/// Map a detected framework's display name (as returned by `detect_web_framework` /
+/// `detect_backend_framework`) to the `package.json` dependency key that carries its version.
+fn node_package_key(framework: &str) -> &str {
+    match framework {
+        "nextjs" => "next",
+        "angular" => "@angular/core",
+        "nestjs" => "@nestjs/core",
+        other => other,
+    }
+}
+
+/// Read the pinned version of a Node dependency (framework or otherwise) out of
+/// `package.json`'s `dependencies`/`devDependencies`, with range prefixes stripped.
+pub fn detect_node_dependency_version(workspace_dir: &PathBuf, framework: &str) -> Option<String> {
+    let content = std::fs::read_to_string(workspace_dir.join("package.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let key = node_package_key(framework);
+
+    let deps = json.get("dependencies").and_then(|d| d.as_object());
+    let dev_deps = json.get("devDependencies").and_then(|d| d.as_object());
+
+    let spec = deps
+        .and_then(|d| d.get(key))
+        .or_else(|| dev_deps.and_then(|d| d.get(key)))
+        .and_then(|v| v.as_str())?;
+
+    Some(strip_semver_prefix(spec))
+}
+
+/// Read `[package].version` from `Cargo.toml`.
+pub fn detect_rust_package_version(workspace_dir: &PathBuf) -> Option<String> {
+    let content = std::fs::read_to_string(workspace_dir.join("Cargo.toml")).ok()?;
+    let toml: toml::Value = toml::from_str(&content).ok()?;
+    toml.get("package")?
+        .get("version")?
+        .as_str()
+        .map(|s| s.to_string())
+}
+
+/// Resolve the locked version of a dependency from `Cargo.lock`'s `[[package]]` tables.
+pub fn detect_rust_lock_version(workspace_dir: &PathBuf, crate_name: &str) -> Option<String> {
+    let content = std::fs::read_to_string(workspace_dir.join("Cargo.lock")).ok()?;
+    let lock: toml::Value = toml::from_str(&content).ok()?;
+    let packages = lock.get("package")?.as_array()?;
+    packages
+        .iter()
+        .find(|pkg| pkg.get("name").and_then(|n| n.as_str()) == Some(crate_name))
+        .and_then(|pkg| pkg.get("version"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+/// Read a Python dependency's pinned version from, in order of preference,
+/// `pyproject.toml`'s `[project].dependencies` / `[tool.poetry.dependencies]`, then a
+/// pinned `==` spec in `requirements.txt`.
+pub fn detect_python_dependency_version(workspace_dir: &PathBuf, package: &str) -> Option<String> {
+    if let Ok(content) = std::fs::read_to_string(workspace_dir.join("pyproject.toml")) {
+        if let Ok(toml) = content.parse::<toml::Value>() {
+            if let Some(deps) = toml.get("project").and_then(|p| p.get("dependencies")).and_then(|d| d.as_array()) {
+                for dep in deps {
+                    if let Some(spec) = dep.as_str() {
+                        if let Some(version) = parse_pep508_version(spec, package) {
+                            return Some(version);
+                        }
+                    }
+                }
+            }
+            if let Some(version) = toml
+                .get("tool")
+                .and_then(|t| t.get("poetry"))
+                .and_then(|p| p.get("dependencies"))
+                .and_then(|d| d.get(package))
+                .and_then(|v| v.as_str())
+            {
+                return Some(strip_semver_prefix(version));
+            }
+        }
+    }
+
+    if let Ok(content) = std::fs::read_to_string(workspace_dir.join("requirements.txt")) {
+        for line in content.lines() {
+            let line = line.trim();
+            if let Some((name, spec)) = line.split_once("==") {
+                if name.trim().eq_ignore_ascii_case(package) {
+                    return Some(spec.trim().to_string());
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Pull a version spec out of a PEP 508 requirement string like `"django>=4.2,<5"`.
+fn parse_pep508_version(spec: &str, package: &str) -> Option<String> {
+    let name_end = spec.find(|c: char| !c.is_alphanumeric() && c != '-' && c != '_').unwrap_or(spec.len());
+    let (name, rest) = spec.split_at(name_end);
+    if !name.eq_ignore_ascii_case(package) {
+        return None;
+    }
+    let version = rest.trim_start_matches(|c: char| c == '=' || c == '>' || c == '<' || c == '~' || c == '!');
+    let version = version.split(',').next().unwrap_or(version).trim();
+    if version.is_empty() {
+        None
+    } else {
+        Some(version.to_string())
+    }
+}
+
+/// Resolve `<groupId>/<artifactId>/<version>` for a Maven dependency from `pom.xml`,
+/// substituting `${property}` placeholders against `<properties>` when the version itself
+/// isn't a literal.
+pub fn detect_maven_dependency_version(
+    workspace_dir: &PathBuf,
+    group_id: &str,
+    artifact_id: &str,
+) -> Option<String> {
+    use quick_xml::events::Event;
+    use quick_xml::reader::Reader;
+
+    let content = std::fs::read_to_string(workspace_dir.join("pom.xml")).ok()?;
+    let mut reader = Reader::from_str(&content);
+    reader.config_mut().trim_text(true);
+
+    let mut properties = std::collections::HashMap::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut current_group = String::new();
+    let mut current_artifact = String::new();
+    let mut current_version = String::new();
+    let mut found = None;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                path.push(String::from_utf8_lossy(e.name().as_ref()).to_string());
+            }
+            Ok(Event::Text(t)) => {
+                let text = t.unescape().unwrap_or_default().to_string();
+                let tag = path.last().cloned().unwrap_or_default();
+                let in_dependency = path.iter().any(|p| p == "dependency");
+                let in_properties = path.len() >= 2 && path[path.len() - 2] == "properties";
+
+                if in_properties {
+                    properties.insert(tag.clone(), text.clone());
+                } else if in_dependency {
+                    match tag.as_str() {
+                        "groupId" => current_group = text,
+                        "artifactId" => current_artifact = text,
+                        "version" => current_version = text,
+                        _ => {}
+                    }
+                }
+            }
+            Ok(Event::End(e)) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                if name == "dependency" {
+                    if current_group == group_id && current_artifact == artifact_id {
+                        found = Some(current_version.clone());
+                    }
+                    current_group.clear();
+                    current_artifact.clear();
+                    current_version.clear();
+                }
+                path.pop();
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let version = found?;
+    if let Some(property_name) = version.strip_prefix("${").and_then(|s| s.strip_suffix('}')) {
+        properties.get(property_name).cloned()
+    } else {
+        Some(version)
+    }
+}
+
+/// Read a PHP dependency's pinned version out of `composer.json`'s `require` map.
+pub fn detect_php_dependency_version(workspace_dir: &PathBuf, package: &str) -> Option<String> {
+    let content = std::fs::read_to_string(workspace_dir.join("composer.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&content).ok()?;
+    json.get("require")?
+        .get(package)?
+        .as_str()
+        .map(strip_semver_prefix)
+}
+
+/// Resolve a gem's locked version from `Gemfile.lock`'s `specs:` section.
+pub fn detect_ruby_gemfile_lock_version(workspace_dir: &PathBuf, gem: &str) -> Option<String> {
+    let content = std::fs::read_to_string(workspace_dir.join("Gemfile.lock")).ok()?;
+    let mut in_specs = false;
+    for line in content.lines() {
+        if line.trim_start() == "specs:" {
+            in_specs = true;
+            continue;
+        }
+        if in_specs {
+            if !line.starts_with("    ") && !line.trim().is_empty() {
+                in_specs = false;
+                continue;
+            }
+            let trimmed = line.trim();
+            if let Some(rest) = trimmed.strip_prefix(&format!("{} (", gem)) {
+                if let Some(version) = rest.strip_suffix(')') {
+                    return Some(version.to_string());
+                }
+            }
+        }
+    }
+    None
+}
@@ -1,18 +1,58 @@
 use anyhow::Result;
 use colored::*;
-use inquire::Select;
+use inquire::{Confirm, Select, Text};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::config::{Config, ConfigManager};
-use crate::fs_ops::FsOperations;
-use crate::llm::{ChatMessage, LlmClient};
+use crate::config::{Config, ConfigManager, RuleDecision};
+use crate::fs_ops::{FsOp, FsOperations};
+use crate::llm::{render_command, validate_tool_args, ChatMessage, LlmClient, ToolCall};
 use crate::security::SecurityValidator;
 use crate::shell::ShellExecutor;
+use crate::shell_session::{Intercepted, ShellSession};
+use crate::ui;
+use crate::ui::{render_section, render_section_footer, render_section_line};
 
 pub struct App {
+    config: Config,
     llm_client: LlmClient,
     security: SecurityValidator,
     messages: Vec<ChatMessage>,
     accept_all: bool,
+    /// Working directory and exported env vars tracked across `execute_shell` tool calls, since
+    /// each call otherwise runs as its own isolated subprocess and would lose a prior `cd`.
+    shell_session: ShellSession,
+    /// Prompt text -> the final (non-tool-call) response it produced, within this session only.
+    /// Lets an identical prompt short-circuit straight to the prior answer instead of
+    /// re-querying the LLM. Invalidated wholesale on an `accept_all` change or a cwd change,
+    /// since either can change what a given prompt would actually do.
+    command_plan_cache: HashMap<String, String>,
+    cache_accept_all: bool,
+    cache_cwd: PathBuf,
+    /// Shell commands the user chose "Always allow this session" for at the approval prompt.
+    /// Separate from `config.whitelist` — never persisted, cleared when the process exits.
+    session_whitelist: HashSet<String>,
+    /// The directory `security` was last resolved for, so `update_system_message_cwd` can tell
+    /// when the shell session's cwd has moved into a directory with its own layered `.aish.toml`
+    /// and rebuild `security` from that directory's merged config.
+    security_cwd: PathBuf,
+}
+
+/// What the user chose at the approval prompt for a proposed operation.
+enum ExecutionDecision {
+    /// Run this text — identical to what was proposed, or the user's edited replacement.
+    Accept(String),
+    /// The user declined this specific call but wants the LLM to keep reasoning, so a tool
+    /// result reporting the decline is fed back instead of aborting the whole `run` loop.
+    Skip,
+    /// The user rejected the operation outright; `run` aborts for this turn.
+    Reject,
+}
+
+fn current_cwd() -> PathBuf {
+    std::env::current_dir().unwrap_or_default()
 }
 
 impl App {
@@ -40,10 +80,17 @@ impl App {
         };
 
         Ok(Self {
+            config,
             llm_client,
             security,
             messages: vec![system_message, user_message],
             accept_all,
+            command_plan_cache: HashMap::new(),
+            cache_accept_all: accept_all,
+            cache_cwd: current_cwd(),
+            shell_session: ShellSession::new(),
+            session_whitelist: HashSet::new(),
+            security_cwd: current_cwd(),
         })
     }
 
@@ -63,10 +110,17 @@ impl App {
         };
 
         Ok(Self {
+            config,
             llm_client,
             security,
             messages: vec![system_message],
             accept_all,
+            command_plan_cache: HashMap::new(),
+            cache_accept_all: accept_all,
+            cache_cwd: current_cwd(),
+            shell_session: ShellSession::new(),
+            session_whitelist: HashSet::new(),
+            security_cwd: current_cwd(),
         })
     }
 
@@ -81,15 +135,136 @@ impl App {
         self.messages.push(user_message);
     }
 
+    /// Watch the shell session's cwd for filesystem changes and drive the normal `run` loop on
+    /// each debounced batch, appending a synthetic user message describing which files changed
+    /// (path + created/modified/removed) instead of waiting for a new prompt. Runs until the
+    /// watcher itself errors out or the process is interrupted (e.g. Ctrl+C).
+    pub async fn run_watching(&mut self) -> Result<()> {
+        use crate::watch;
+        use std::sync::mpsc::RecvTimeoutError;
+
+        let root = self.shell_session.cwd().clone();
+        println!(
+            "{}",
+            format!("→ Watching {} for changes (Ctrl+C to stop)...", root.display()).bright_cyan()
+        );
+
+        let (_watcher, rx) = watch::spawn_watcher(&root)?;
+        let mut pending: Vec<watch::FileChange> = Vec::new();
+
+        loop {
+            let timeout = if pending.is_empty() {
+                Duration::from_secs(3600)
+            } else {
+                watch::DEBOUNCE
+            };
+
+            match rx.recv_timeout(timeout) {
+                Ok(Ok(event)) => {
+                    pending.extend(watch::classify_event(&event, &self.security));
+                }
+                Ok(Err(e)) => {
+                    eprintln!(
+                        "{} {}",
+                        "×".bright_red(),
+                        format!("Watch error: {}", e).bright_red()
+                    );
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if pending.is_empty() {
+                        continue;
+                    }
+                    let changes = watch::dedupe(std::mem::take(&mut pending));
+                    let description = watch::describe_changes(&changes);
+
+                    println!();
+                    println!("{}", "→ Detected changes:".bright_yellow());
+                    println!("{}", description);
+
+                    self.add_user_message(format!(
+                        "The following files changed on disk while watching this workspace:\n{}\n\
+                         Continue the task accordingly (e.g. re-run affected tests or pick up where you left off).",
+                        description
+                    ));
+
+                    if let Err(e) = self.run().await {
+                        eprintln!(
+                            "{} {}",
+                            "×".bright_red(),
+                            format!("Error: {}", e).bright_red()
+                        );
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    anyhow::bail!("File watcher channel disconnected unexpectedly");
+                }
+            }
+        }
+    }
+
     pub async fn run(&mut self) -> Result<()> {
+        self.invalidate_cache_if_stale();
+        let json_mode = ui::format::is_json();
+
+        if let Some(prompt) = self.latest_user_prompt() {
+            if let Some(cached) = self.command_plan_cache.get(&prompt) {
+                if json_mode {
+                    println!(
+                        "{}",
+                        serde_json::json!({ "kind": "result", "content": cached, "cached": true })
+                    );
+                } else {
+                    println!();
+                    println!("{}", "→ LLM Response (cached):".bright_cyan());
+                    println!("{}", cached);
+                }
+                return Ok(());
+            }
+        }
+
+        let max_steps = self.config.llm.max_steps;
+        let mut step: u32 = 0;
+
         loop {
-            println!();
-            println!("{}", "→ LLM is Thinking...".bright_cyan());
+            step += 1;
+            if step > max_steps {
+                anyhow::bail!(
+                    "Stopped after {} step(s): hit llm.max_steps without a final answer. \
+                     Raise llm.max_steps if this prompt genuinely needs more tool calls.",
+                    max_steps
+                );
+            }
+
+            if !json_mode {
+                render_section(&format!("Step {}/{}", step, max_steps), Color::Cyan);
+                render_section_line("status:", "LLM is thinking...".bright_cyan());
+                render_section_footer();
+            }
 
             // Update system message with current CWD before each request
             self.update_system_message_cwd()?;
 
-            let response = match self.llm_client.chat(self.messages.clone()).await {
+            // Print content deltas as they arrive instead of leaving the user staring at a
+            // blank prompt until the whole response lands; tool-call-only responses never
+            // carry content deltas, so this is a no-op on those steps. Suppressed in JSON
+            // mode, which emits the full content as one structured object once the loop ends.
+            let mut streamed_header = false;
+            let response = match self
+                .llm_client
+                .chat_stream(self.messages.clone(), |delta| {
+                    if json_mode {
+                        return;
+                    }
+                    if !streamed_header {
+                        streamed_header = true;
+                        println!();
+                        println!("{}", "→ LLM Response:".bright_cyan());
+                    }
+                    print!("{}", delta);
+                    let _ = std::io::stdout().flush();
+                })
+                .await
+            {
                 Ok(r) => r,
                 Err(e) => {
                     eprintln!(
@@ -100,27 +275,67 @@ impl App {
                     return Err(e);
                 }
             };
+            if streamed_header {
+                println!();
+            }
 
             // Always add the response to messages first
             self.messages.push(response.clone());
 
             if let Some(tool_calls) = &response.tool_calls {
+                // Approved fs ops are queued here instead of run immediately so independent
+                // reads/writes requested in the same turn execute concurrently via
+                // `FsOperations::run_batch` rather than one at a time.
+                let mut pending_fs_ops: Vec<(String, String, FsOp)> = Vec::new();
+
                 for tool_call in tool_calls {
                     let args: serde_json::Value =
                         serde_json::from_str(&tool_call.function.arguments)?;
 
+                    if let Err(reason) = self.validate_tool_call(tool_call, &args) {
+                        println!(
+                            "{} {}",
+                            "×".bright_red(),
+                            format!("Invalid tool call '{}': {}", tool_call.function.name, reason)
+                                .bright_red()
+                        );
+                        self.add_tool_result(
+                            &tool_call.id,
+                            &tool_call.function.name,
+                            &format!(
+                                "Error: invalid arguments for '{}': {}",
+                                tool_call.function.name, reason
+                            ),
+                        )
+                        .await?;
+                        continue;
+                    }
+
                     match tool_call.function.name.as_str() {
                         "execute_shell" => {
                             if let Some(command) = args.get("command").and_then(|c| c.as_str()) {
-                                let should_execute = self.should_execute("shell", command).await?;
-                                if should_execute {
-                                    let result = self.execute_action("shell", command).await?;
-                                    self.add_tool_result(&tool_call.id, "shell", &result)
+                                // Expand aliases before validation ever sees the command, so a
+                                // whitelist/blocklist pattern can't be smuggled past via an alias.
+                                let command = crate::shell::expand_aliases(command, &self.config.aliases);
+                                match self.should_execute("shell", &command).await? {
+                                    ExecutionDecision::Accept(command) => {
+                                        let result =
+                                            self.execute_action("shell", &command).await?;
+                                        self.add_tool_result(&tool_call.id, "shell", &result)
+                                            .await?;
+                                    }
+                                    ExecutionDecision::Skip => {
+                                        self.add_tool_result(
+                                            &tool_call.id,
+                                            "shell",
+                                            "User declined to run this command.",
+                                        )
                                         .await?;
-                                } else {
-                                    println!("{}", "× Command rejected".bright_red());
-                                    // In interactive mode, continue instead of returning
-                                    return Ok(());
+                                    }
+                                    ExecutionDecision::Reject => {
+                                        // In interactive mode, continue instead of returning
+                                        return Ok(());
+                                    }
                                 }
                             }
                         }
@@ -128,25 +343,92 @@ impl App {
                             let operation = tool_call.function.name.clone();
                             let operation_desc = self.format_operation(&operation, &args);
 
-                            let should_execute =
-                                self.should_execute(&operation, &operation_desc).await?;
-                            if should_execute {
-                                let result =
-                                    self.execute_action(&operation, &args.to_string()).await?;
-                                self.add_tool_result(&tool_call.id, &operation, &result)
+                            match self.should_execute(&operation, &operation_desc).await? {
+                                ExecutionDecision::Accept(_) => {
+                                    let op = self.fs_op_from_args(&operation, &args)?;
+                                    pending_fs_ops.push((tool_call.id.clone(), operation, op));
+                                }
+                                ExecutionDecision::Skip => {
+                                    self.add_tool_result(
+                                        &tool_call.id,
+                                        &operation,
+                                        "User declined this operation.",
+                                    )
                                     .await?;
-                            } else {
-                                println!("{}", "× Operation rejected".bright_red());
-                                return Ok(());
+                                }
+                                ExecutionDecision::Reject => {
+                                    return Ok(());
+                                }
+                            }
+                        }
+                        name => {
+                            if let Some(tool) = self.llm_client.custom_tool(name).cloned() {
+                                let command = render_command(&tool, &args);
+                                let command = crate::shell::expand_aliases(&command, &self.config.aliases);
+                                match self.should_execute("shell", &command).await? {
+                                    ExecutionDecision::Accept(command) => {
+                                        let result =
+                                            self.execute_action("shell", &command).await?;
+                                        self.add_tool_result(&tool_call.id, name, &result)
+                                            .await?;
+                                    }
+                                    ExecutionDecision::Skip => {
+                                        self.add_tool_result(
+                                            &tool_call.id,
+                                            name,
+                                            "User declined to run this command.",
+                                        )
+                                        .await?;
+                                    }
+                                    ExecutionDecision::Reject => {
+                                        return Ok(());
+                                    }
+                                }
                             }
                         }
-                        _ => {}
+                    }
+                }
+
+                if !pending_fs_ops.is_empty() {
+                    if !json_mode {
+                        render_section("Batch fs operations", Color::Cyan);
+                        render_section_line(
+                            "count:",
+                            pending_fs_ops.len().to_string().bright_white(),
+                        );
+                        render_section_footer();
+                    }
+
+                    let ops: Vec<FsOp> =
+                        pending_fs_ops.iter().map(|(_, _, op)| op.clone()).collect();
+                    let results =
+                        FsOperations::run_batch(ops, self.config.security.max_parallelism);
+
+                    for ((tool_call_id, operation, _), result) in
+                        pending_fs_ops.into_iter().zip(results)
+                    {
+                        let result = result?;
+                        self.add_tool_result(&tool_call_id, &operation, &result)
+                            .await?;
                     }
                 }
             } else if let Some(content) = &response.content {
-                println!();
-                println!("{}", "→ LLM Response:".bright_cyan());
-                println!("{}", content);
+                if json_mode {
+                    // The streamed on_delta printing above is suppressed in JSON mode, so this
+                    // is the only place the answer is rendered: one structured object instead
+                    // of the "→ LLM Response:" header plus raw content.
+                    println!(
+                        "{}",
+                        serde_json::json!({ "kind": "result", "content": content })
+                    );
+                }
+                // Otherwise already printed incrementally above: every provider's chat_stream
+                // invokes on_delta at least once with the full content when it has no native
+                // streaming support (the default LlmProvider::chat_stream impl).
+
+                if let Some(prompt) = self.latest_user_prompt() {
+                    self.command_plan_cache.insert(prompt, content.clone());
+                }
                 break;
             }
         }
@@ -154,7 +436,68 @@ impl App {
         Ok(())
     }
 
-    async fn should_execute(&self, op_type: &str, description: &str) -> Result<bool> {
+    /// Drop the whole cache when `accept_all` or the shell session's cwd has changed since the
+    /// last `run()` — either can change what an identical prompt would actually do.
+    fn invalidate_cache_if_stale(&mut self) {
+        let cwd = self.shell_session.cwd().clone();
+        if self.accept_all != self.cache_accept_all || cwd != self.cache_cwd {
+            self.command_plan_cache.clear();
+            self.cache_accept_all = self.accept_all;
+            self.cache_cwd = cwd;
+        }
+    }
+
+    fn latest_user_prompt(&self) -> Option<String> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|m| m.role == "user")
+            .and_then(|m| m.content.clone())
+    }
+
+    /// Check a returned `ToolCall`'s arguments against the matching tool's declared
+    /// `parameters` schema before it's allowed anywhere near `execute_action`.
+    fn validate_tool_call(&self, tool_call: &ToolCall, args: &serde_json::Value) -> Result<(), String> {
+        let schema = self
+            .llm_client
+            .tools()
+            .into_iter()
+            .find(|tool| {
+                tool.get("function").and_then(|f| f.get("name")).and_then(|n| n.as_str())
+                    == Some(tool_call.function.name.as_str())
+            })
+            .and_then(|tool| tool.get("function")?.get("parameters").cloned());
+
+        match schema {
+            Some(parameters) => validate_tool_args(&parameters, args),
+            None => Ok(()),
+        }
+    }
+
+    async fn should_execute(&mut self, op_type: &str, description: &str) -> Result<ExecutionDecision> {
+        // The blocklist is checked before anything else, including accept_all, so a matching
+        // command is never auto-approved in unattended mode.
+        if op_type == "shell" && self.security.is_blocked(description) {
+            println!(
+                "{} {}",
+                "×".bright_red(),
+                format!("Blocked by policy: {}", description).bright_red()
+            );
+            return Ok(ExecutionDecision::Reject);
+        }
+
+        // A command the user previously chose "Always allow this session" for auto-approves
+        // without touching the persisted whitelist.
+        if op_type == "shell" && self.session_whitelist.contains(description) {
+            println!(
+                "{} {}",
+                "+".bright_green(),
+                format!("Auto-approved (always allowed this session): {}", description)
+                    .bright_green()
+            );
+            return Ok(ExecutionDecision::Accept(description.to_string()));
+        }
+
         // Whitelist only applies when accept_all is true
         // If accept_all is false, always ask user (whitelist is ignored)
         if self.accept_all {
@@ -174,7 +517,7 @@ impl App {
                     format!("Auto-approved: {}", description).bright_green()
                 );
             }
-            return Ok(true);
+            return Ok(ExecutionDecision::Accept(description.to_string()));
         }
 
         // Otherwise, ask for user approval (whitelist is ignored)
@@ -184,22 +527,111 @@ impl App {
             format!("→ Proposed {}: {}", op_type, description).bright_yellow()
         );
 
-        let options = vec!["Accept", "Reject"];
+        // Editing and session-scoped whitelisting only make sense for an actual shell command,
+        // not for the fixed fs op descriptions `format_operation` produces.
+        let options: Vec<&str> = if op_type == "shell" {
+            vec!["Accept", "Edit", "Always allow this session", "Skip", "Reject"]
+        } else {
+            vec!["Accept", "Skip", "Reject"]
+        };
+
         let choice = Select::new("What would you like to do?", options)
             .with_help_message("Use ↑/↓ to navigate, Enter to select")
             .prompt()?;
 
-        Ok(choice == "Accept")
+        match choice {
+            "Accept" => Ok(ExecutionDecision::Accept(description.to_string())),
+            "Edit" => {
+                let edited = Text::new("Edit command:")
+                    .with_initial_value(description)
+                    .prompt()?;
+                let edited = crate::shell::expand_aliases(&edited, &self.config.aliases);
+
+                // The edited command is user-controlled input, same as a freshly proposed one,
+                // so it must clear the same blocklist check before being accepted.
+                if self.security.is_blocked(&edited) {
+                    println!(
+                        "{} {}",
+                        "×".bright_red(),
+                        format!("Blocked by policy: {}", edited).bright_red()
+                    );
+                    return Ok(ExecutionDecision::Reject);
+                }
+
+                Ok(ExecutionDecision::Accept(edited))
+            }
+            "Always allow this session" => {
+                self.session_whitelist.insert(description.to_string());
+                println!(
+                    "{} {}",
+                    "+".bright_green(),
+                    "Always allowing this exact command for the rest of the session".bright_green()
+                );
+                Ok(ExecutionDecision::Accept(description.to_string()))
+            }
+            "Skip" => {
+                println!("{}", "→ Skipped — continuing".bright_yellow());
+                Ok(ExecutionDecision::Skip)
+            }
+            _ => {
+                let label = if op_type == "shell" { "Command" } else { "Operation" };
+                println!("{}", format!("× {} rejected", label).bright_red());
+                Ok(ExecutionDecision::Reject)
+            }
+        }
     }
 
-    async fn execute_action(&self, op_type: &str, command: &str) -> Result<String> {
+    async fn execute_action(&mut self, op_type: &str, command: &str) -> Result<String> {
         self.security.validate_operation(op_type)?;
 
         println!();
         println!("{}", "> Executing...".bright_cyan());
 
         match op_type {
-            "shell" => ShellExecutor::execute(command),
+            "shell" => {
+                if let Some(intercepted) = self.shell_session.intercept(command, &self.security)? {
+                    return Ok(match intercepted {
+                        Intercepted::Cd => {
+                            format!("Changed directory to {}", self.shell_session.cwd().display())
+                        }
+                        Intercepted::Assignment => "Environment updated".to_string(),
+                    });
+                }
+
+                let cwd = self.shell_session.cwd().to_string_lossy().to_string();
+
+                let mut env = self.config.env.clone();
+                env.extend(self.shell_session.env().clone());
+
+                match self.security.evaluate_command(command, &cwd) {
+                    RuleDecision::Deny { pattern } => {
+                        anyhow::bail!("Command denied by command_rules (matched '{}')", pattern)
+                    }
+                    RuleDecision::Confirm => {
+                        let proceed = Confirm::new(&format!(
+                            "A command rule requires confirmation — run '{}'?",
+                            command
+                        ))
+                        .with_default(false)
+                        .prompt()?;
+                        if !proceed {
+                            anyhow::bail!("Command rejected at command_rules confirmation gate");
+                        }
+                        ShellExecutor::execute(
+                            command,
+                            &env,
+                            self.config.security.run_as_user.as_deref(),
+                            self.shell_session.cwd(),
+                        )
+                    }
+                    RuleDecision::Allow => ShellExecutor::execute(
+                        command,
+                        &env,
+                        self.config.security.run_as_user.as_deref(),
+                        self.shell_session.cwd(),
+                    ),
+                }
+            }
             "fs_readfile" => {
                 let args: serde_json::Value = serde_json::from_str(command)?;
                 let path = args["path"].as_str().unwrap();
@@ -232,6 +664,46 @@ impl App {
         }
     }
 
+    /// Validate and translate an approved fs tool call into an `FsOp` for `run_batch`, without
+    /// executing it — mirrors the validation `execute_action` does for the same operations.
+    fn fs_op_from_args(&self, operation: &str, args: &serde_json::Value) -> Result<FsOp> {
+        self.security.validate_operation(operation)?;
+
+        match operation {
+            "fs_readfile" => {
+                let path = args["path"].as_str().unwrap();
+                self.security.validate_path(path)?;
+                Ok(FsOp::Read {
+                    path: path.to_string(),
+                })
+            }
+            "fs_writefile" => {
+                let path = args["path"].as_str().unwrap();
+                let content = args["content"].as_str().unwrap();
+                self.security.validate_path(path)?;
+                Ok(FsOp::Write {
+                    path: path.to_string(),
+                    content: content.to_string(),
+                })
+            }
+            "fs_makedir" => {
+                let path = args["path"].as_str().unwrap();
+                self.security.validate_path(path)?;
+                Ok(FsOp::MakeDir {
+                    path: path.to_string(),
+                })
+            }
+            "fs_listdir" => {
+                let path = args["path"].as_str().unwrap();
+                self.security.validate_path(path)?;
+                Ok(FsOp::ListDir {
+                    path: path.to_string(),
+                })
+            }
+            _ => anyhow::bail!("Unknown operation"),
+        }
+    }
+
     fn format_operation(&self, operation: &str, args: &serde_json::Value) -> String {
         match operation {
             "fs_readfile" => {
@@ -269,19 +741,27 @@ impl App {
 
     fn update_system_message_cwd(&mut self) -> Result<()> {
         use crate::workspace_context::WorkspaceContext;
-        use std::path::PathBuf;
-        
+
+        let workspace_dir = self.shell_session.cwd().clone();
+
+        // Re-resolve the layered config for the new directory and rebuild `security` from it,
+        // so e.g. a subdirectory's stricter `.aish.toml` (`allow_absolute_paths = false`, an
+        // extra `blocklist` entry, ...) takes effect as soon as a `cd` lands there.
+        if workspace_dir != self.security_cwd {
+            let layered = ConfigManager::new()?.load_layered_config_for(&workspace_dir)?;
+            self.security = SecurityValidator::new(layered.config)?;
+            self.security_cwd = workspace_dir.clone();
+        }
+
         if let Some(system_msg) = self.messages.first_mut() {
             if let Some(content) = &mut system_msg.content {
-                // Update CWD
-                let cwd = std::env::current_dir()
-                    .map(|p| p.to_string_lossy().to_string())
-                    .unwrap_or_else(|_| "unknown".to_string());
+                // Update CWD to the shell session's tracked cwd, not aish's own process cwd, so
+                // the model sees the effect of a prior `cd`.
+                let cwd = workspace_dir.to_string_lossy().to_string();
                 *content = content.replace("{{CWD}}", &cwd);
-                
+
                 // Update workspace flags
-                let workspace_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-                let workspace_context = WorkspaceContext::detect(&workspace_dir);
+                let workspace_context = WorkspaceContext::detect_cached(&workspace_dir);
                 let flags = workspace_context.to_flags_string();
                 *content = content.replace("{{FLAGS}}", &flags);
             }
@@ -4,7 +4,7 @@ use inquire::{Confirm, Password, Select, Text};
 use crate::config::{Config, ConfigManager};
 use crate::llm::LlmClient;
 
-const PROVIDERS: &[(&str, &str, &str)] = &[
+pub const PROVIDERS: &[(&str, &str, &str)] = &[
     ("OpenAI", "https://api.openai.com/v1", "gpt-4"),
     ("OpenRouter", "https://openrouter.ai/api/v1", "openai/gpt-4"),
     ("Custom", "", ""),
@@ -100,6 +100,92 @@ pub async fn run_init_wizard(config_manager: &ConfigManager) -> Result<()> {
         .prompt()?;
     config.security.allow_config_path_access = allow_config;
 
+    // 9. Run-as-user (optional, only meaningful when aish itself runs as root)
+    let configure_run_as = Confirm::new("run_as_user:")
+        .with_default(false)
+        .with_help_message("y/n - run generated shell commands as a different local user")
+        .prompt()?;
+
+    if configure_run_as {
+        let username = Text::new("run_as_user (username):")
+            .with_help_message("Commands will drop privileges to this user before running")
+            .prompt()?;
+        if !username.trim().is_empty() {
+            config.security.run_as_user = Some(username.trim().to_string());
+        }
+    }
+
+    // 10. Per-command permission rules (optional)
+    let configure_rules = Confirm::new("command_rules:")
+        .with_default(false)
+        .with_help_message("y/n - add allow/deny/confirm rules for specific command patterns")
+        .prompt()?;
+
+    if configure_rules {
+        loop {
+            let pattern = Text::new("command pattern regex (blank to finish, must start with ^):")
+                .with_help_message("e.g. ^rm\\s+-rf\\s+/")
+                .prompt()?;
+            if pattern.trim().is_empty() {
+                break;
+            }
+            if !pattern.starts_with('^') {
+                println!("Pattern must start with '^' — skipped.");
+                continue;
+            }
+
+            let action = Select::new("action:", vec!["allow", "deny", "confirm"])
+                .with_help_message("Use ↑/↓ to navigate, Enter to select")
+                .prompt()?;
+            let action = match action {
+                "allow" => crate::config::RuleAction::Allow,
+                "deny" => crate::config::RuleAction::Deny,
+                _ => crate::config::RuleAction::Confirm,
+            };
+
+            config.security.command_rules.push(crate::config::CommandRule {
+                pattern: pattern.trim().to_string(),
+                action,
+                target_user: None,
+                working_dir: None,
+            });
+        }
+    }
+
+    // 11. Aliases and env overrides (optional)
+    let configure_aliases = Confirm::new("configure_aliases:")
+        .with_default(false)
+        .with_help_message("y/n - add command aliases / env vars for generated shell commands")
+        .prompt()?;
+
+    if configure_aliases {
+        loop {
+            let alias_name = Text::new("alias name (blank to finish):")
+                .with_help_message("e.g. ll")
+                .prompt()?;
+            if alias_name.trim().is_empty() {
+                break;
+            }
+            let alias_value = Text::new(&format!("alias value for '{}':", alias_name))
+                .with_help_message("e.g. eza -la")
+                .prompt()?;
+            config.aliases.insert(alias_name.trim().to_string(), alias_value);
+        }
+
+        loop {
+            let env_name = Text::new("env var name (blank to finish):")
+                .with_help_message("e.g. EDITOR")
+                .prompt()?;
+            if env_name.trim().is_empty() {
+                break;
+            }
+            let env_value = Text::new(&format!("value for '{}':", env_name))
+                .with_help_message("e.g. nvim")
+                .prompt()?;
+            config.env.insert(env_name.trim().to_string(), env_value);
+        }
+    }
+
     // Save configuration
     config_manager.save_config(&config)?;
     config_manager.save_api_key(&api_key)?;
@@ -0,0 +1,4 @@
+pub mod app;
+pub mod completions;
+pub mod init;
+pub mod policy;
@@ -0,0 +1,231 @@
+use anyhow::{bail, Context, Result};
+use clap::{Subcommand, ValueEnum};
+use colored::*;
+use regex::Regex;
+
+use crate::config::{Config, ConfigManager};
+use crate::ui;
+
+/// Which list a `policy add`/`policy rm` targets.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum PolicyList {
+    Whitelist,
+    Blocklist,
+    BlockedExtensions,
+}
+
+impl PolicyList {
+    fn label(self) -> &'static str {
+        match self {
+            PolicyList::Whitelist => "whitelist",
+            PolicyList::Blocklist => "blocklist",
+            PolicyList::BlockedExtensions => "blocked_extensions",
+        }
+    }
+}
+
+/// `aish policy` subcommands: a safe, discoverable surface over the same `Config`/
+/// `ConfigManager` that `SecurityValidator` consumes, so whitelist/blocklist/blocked-extension
+/// patterns and per-operation permissions don't have to be hand-edited into the TOML file.
+#[derive(Subcommand)]
+pub enum PolicyCommand {
+    /// List the whitelist, blocklist, blocked extensions, and operation permissions currently
+    /// in effect.
+    Ls,
+    /// Add a pattern to the whitelist, blocklist, or blocked extensions. Whitelist/blocklist
+    /// entries are validated as a compiling regex before being saved, unlike the silent
+    /// `filter_map(..).ok()` `SecurityValidator` falls back to at load time.
+    Add {
+        #[arg(value_enum)]
+        list: PolicyList,
+        pattern: String,
+    },
+    /// Remove a pattern from the whitelist, blocklist, or blocked extensions.
+    Rm {
+        #[arg(value_enum)]
+        list: PolicyList,
+        pattern: String,
+    },
+    /// Allow an operation (fs.readfile, fs.writefile, fs.makedir, fs.makefile, fs.listdir, shell).
+    Allow { operation: String },
+    /// Deny an operation (fs.readfile, fs.writefile, fs.makedir, fs.makefile, fs.listdir, shell).
+    Deny { operation: String },
+}
+
+pub fn run(config_manager: &ConfigManager, command: PolicyCommand) -> Result<()> {
+    match command {
+        PolicyCommand::Ls => {
+            let config = config_manager.load_config()?;
+            print_policy(&config);
+        }
+        PolicyCommand::Add { list, pattern } => {
+            add(config_manager, list, &pattern)?;
+            println!(
+                "{} Added to {}: {}",
+                "✓".green(),
+                list.label().bright_cyan(),
+                pattern.bright_yellow()
+            );
+        }
+        PolicyCommand::Rm { list, pattern } => {
+            let removed = remove(config_manager, list, &pattern)?;
+            if removed {
+                println!(
+                    "{} Removed from {}: {}",
+                    "✓".green(),
+                    list.label().bright_cyan(),
+                    pattern.bright_yellow()
+                );
+            } else {
+                println!(
+                    "{} Not found in {}: {}",
+                    "×".bright_red(),
+                    list.label().bright_cyan(),
+                    pattern.bright_yellow()
+                );
+            }
+        }
+        PolicyCommand::Allow { operation } => {
+            set_operation(config_manager, &operation, true)?;
+            println!("{} Allowed operation: {}", "✓".green(), operation.bright_cyan());
+        }
+        PolicyCommand::Deny { operation } => {
+            set_operation(config_manager, &operation, false)?;
+            println!("{} Denied operation: {}", "✓".green(), operation.bright_cyan());
+        }
+    }
+    Ok(())
+}
+
+/// Validate (for whitelist/blocklist) that `pattern` compiles as a `Regex`, then append it to
+/// the targeted list if it isn't already present.
+fn add(config_manager: &ConfigManager, list: PolicyList, pattern: &str) -> Result<()> {
+    if matches!(list, PolicyList::Whitelist | PolicyList::Blocklist) {
+        Regex::new(pattern).with_context(|| format!("'{}' is not a valid regex", pattern))?;
+    }
+
+    let mut config = config_manager.load_config()?;
+    let target = target_list(&mut config, list);
+    if !target.contains(&pattern.to_string()) {
+        target.push(pattern.to_string());
+    }
+    config_manager.save_config(&config)
+}
+
+/// Remove `pattern` from the targeted list, returning whether it was actually present.
+fn remove(config_manager: &ConfigManager, list: PolicyList, pattern: &str) -> Result<bool> {
+    let mut config = config_manager.load_config()?;
+    let target = target_list(&mut config, list);
+    let before = target.len();
+    target.retain(|p| p != pattern);
+    let removed = target.len() != before;
+    config_manager.save_config(&config)?;
+    Ok(removed)
+}
+
+fn target_list(config: &mut Config, list: PolicyList) -> &mut Vec<String> {
+    match list {
+        PolicyList::Whitelist => &mut config.whitelist,
+        PolicyList::Blocklist => &mut config.blocklist,
+        PolicyList::BlockedExtensions => &mut config.security.blocked_extensions,
+    }
+}
+
+fn set_operation(config_manager: &ConfigManager, operation: &str, allowed: bool) -> Result<()> {
+    let mut config = config_manager.load_config()?;
+    let perms = &mut config.security.allowed_operations;
+    match operation {
+        "fs.makedir" => perms.fs_makedir = allowed,
+        "fs.makefile" => perms.fs_makefile = allowed,
+        "fs.writefile" => perms.fs_writefile = allowed,
+        "fs.readfile" => perms.fs_readfile = allowed,
+        "fs.listdir" => perms.fs_listdir = allowed,
+        "shell" => perms.shell = allowed,
+        other => bail!(
+            "Unknown operation '{}' (expected one of fs.makedir, fs.makefile, fs.writefile, fs.readfile, fs.listdir, shell)",
+            other
+        ),
+    }
+    config_manager.save_config(&config)
+}
+
+fn print_policy(config: &Config) {
+    if ui::format::is_json() {
+        println!(
+            "{}",
+            serde_json::json!({
+                "whitelist": config.whitelist,
+                "blocklist": config.blocklist,
+                "blocked_extensions": config.security.blocked_extensions,
+                "allowed_operations": config.security.allowed_operations,
+            })
+        );
+        return;
+    }
+
+    if ui::plain_info().suppress_boxes {
+        for item in &config.whitelist {
+            println!("whitelist: {}", item);
+        }
+        for item in &config.blocklist {
+            println!("blocklist: {}", item);
+        }
+        for ext in &config.security.blocked_extensions {
+            println!("blocked_extensions: {}", ext);
+        }
+        print_operations_plain(config);
+        return;
+    }
+
+    println!("{}", "┌─ Whitelist".bright_blue().bold());
+    if config.whitelist.is_empty() {
+        println!("{} {}", "│".bright_black(), "(empty)".bright_black());
+    }
+    for item in &config.whitelist {
+        println!("{} {}", "│".bright_black(), item.bright_green());
+    }
+    println!("{}", "└─────────────────────────────────────────────────────".bright_black());
+
+    println!("{}", "┌─ Blocklist".bright_blue().bold());
+    if config.blocklist.is_empty() {
+        println!("{} {}", "│".bright_black(), "(empty)".bright_black());
+    }
+    for item in &config.blocklist {
+        println!("{} {}", "│".bright_black(), item.bright_red());
+    }
+    println!("{}", "└─────────────────────────────────────────────────────".bright_black());
+
+    println!("{}", "┌─ Blocked Extensions".bright_blue().bold());
+    if config.security.blocked_extensions.is_empty() {
+        println!("{} {}", "│".bright_black(), "(empty)".bright_black());
+    }
+    for ext in &config.security.blocked_extensions {
+        println!("{} {}", "│".bright_black(), ext.bright_red());
+    }
+    println!("{}", "└─────────────────────────────────────────────────────".bright_black());
+
+    println!("{}", "┌─ Operation Permissions".bright_blue().bold());
+    let perms = &config.security.allowed_operations;
+    for (name, allowed) in [
+        ("fs.makedir", perms.fs_makedir),
+        ("fs.makefile", perms.fs_makefile),
+        ("fs.writefile", perms.fs_writefile),
+        ("fs.readfile", perms.fs_readfile),
+        ("fs.listdir", perms.fs_listdir),
+        ("shell", perms.shell),
+    ] {
+        let value = if allowed { "true".bright_green() } else { "false".bright_red() };
+        println!("{} {} {}", "│".bright_black(), format!("{}:", name).bright_white(), value);
+    }
+    println!("{}", "└─────────────────────────────────────────────────────".bright_black());
+}
+
+fn print_operations_plain(config: &Config) {
+    let perms = &config.security.allowed_operations;
+    println!("allowed_operations.fs_makedir: {}", perms.fs_makedir);
+    println!("allowed_operations.fs_makefile: {}", perms.fs_makefile);
+    println!("allowed_operations.fs_writefile: {}", perms.fs_writefile);
+    println!("allowed_operations.fs_readfile: {}", perms.fs_readfile);
+    println!("allowed_operations.fs_listdir: {}", perms.fs_listdir);
+    println!("allowed_operations.shell: {}", perms.shell);
+}
@@ -1,5 +1,7 @@
 use anyhow::{Context, Result};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
@@ -11,6 +13,60 @@ pub struct Config {
     pub llm: LlmConfig,
     pub security: SecurityConfig,
     pub whitelist: Vec<String>,
+    /// Regex patterns that are never allowed to run, even under `--accept-all` — evaluated
+    /// before the whitelist/accept-all auto-approval path ever gets a chance to short-circuit.
+    #[serde(default)]
+    pub blocklist: Vec<String>,
+    /// Path glob patterns `SecurityValidator::validate_path` treats as ignored, merged
+    /// additively across layers the same way `whitelist`/`blocklist` are — in addition to (not
+    /// instead of) the global `~/.aish/.aishignore` file.
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    /// User-declared tools, each backed by a shell command template, merged into the
+    /// tool definitions advertised to the model alongside the five built-ins.
+    #[serde(default)]
+    pub custom_tools: Vec<CustomToolDef>,
+    /// Command-name rewrites applied to the first token of a shell command before it runs
+    /// (e.g. `ls` -> `eza`), so generated commands resolve the way the user's own shell would.
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    /// Extra environment variables injected into every spawned shell command.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Settings for interactive (`-i`/`--interactive`) mode.
+    #[serde(default)]
+    pub interactive: InteractiveConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InteractiveConfig {
+    /// Max prompts kept in the persisted history file, oldest evicted first. `0` disables the
+    /// cap (unbounded history).
+    #[serde(default = "default_history_size")]
+    pub history_size: usize,
+}
+
+fn default_history_size() -> usize {
+    500
+}
+
+impl Default for InteractiveConfig {
+    fn default() -> Self {
+        Self {
+            history_size: default_history_size(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CustomToolDef {
+    pub name: String,
+    pub description: String,
+    /// JSON-Schema `parameters` object, same shape the built-in tools declare.
+    pub parameters: serde_json::Value,
+    /// Shell command template; `{placeholder}` tokens are substituted with the matching
+    /// argument from the model's tool call.
+    pub command: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -19,6 +75,33 @@ pub struct LlmConfig {
     pub api_url: String,
     pub model: String,
     pub max_tokens: u32,
+
+    // Vertex AI / Gemini backend settings (ignored by other providers)
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub adc_file: Option<String>,
+
+    /// Retries for a single model on 429/5xx before falling back or giving up.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Models to try, in order, once `max_retries` is exhausted on the primary model.
+    #[serde(default)]
+    pub fallback_models: Vec<String>,
+    /// Cap on how many tool-calling round-trips `App::run` makes for a single user prompt
+    /// before it stops and reports that the step budget was exhausted.
+    #[serde(default = "default_max_steps")]
+    pub max_steps: u32,
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_max_steps() -> u32 {
+    8
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -27,6 +110,110 @@ pub struct SecurityConfig {
     pub allow_config_path_access: bool,
     pub blocked_extensions: Vec<String>,
     pub allowed_operations: OperationPermissions,
+    /// Local username to drop privileges to before running shell commands. Only takes effect
+    /// on Unix, and only makes sense when aish itself is running as root; `None` (the default)
+    /// leaves commands running as whatever user launched aish.
+    #[serde(default)]
+    pub run_as_user: Option<String>,
+    /// Ordered sudoers/pleaser-style rules evaluated against a proposed shell command before
+    /// `allowed_operations.shell` even comes into play: first match wins. Empty (the default)
+    /// keeps today's behavior of only gating on the coarse `shell` boolean; once a rule is
+    /// added, anything that falls through the whole list without matching is denied.
+    #[serde(default)]
+    pub command_rules: Vec<CommandRule>,
+    /// Cap on concurrent worker threads for `FsOperations::run_batch`. `0` (the default) means
+    /// "auto" — size the pool to `num_cpus::get()`. Set to `1` to force single-threaded batches.
+    #[serde(default)]
+    pub max_parallelism: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    Allow,
+    Deny,
+    Confirm,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommandRule {
+    /// Must start with `^` — an unanchored pattern can silently match less than the author
+    /// intended (e.g. a `deny` rule for `rm -rf /` that never fires because the command was
+    /// built up with a leading `sudo `), which is validated away at load time.
+    pub pattern: String,
+    pub action: RuleAction,
+    #[serde(default)]
+    pub target_user: Option<String>,
+    /// Working-directory prefix this rule is restricted to; `None` matches any cwd.
+    #[serde(default)]
+    pub working_dir: Option<String>,
+}
+
+impl CommandRule {
+    fn compiled(&self) -> Result<Regex> {
+        Regex::new(&self.pattern).with_context(|| format!("Invalid command rule pattern: {}", self.pattern))
+    }
+
+    fn matches(&self, command: &str, cwd: &str, target_user: Option<&str>) -> bool {
+        if let Some(ref rule_user) = self.target_user {
+            if Some(rule_user.as_str()) != target_user {
+                return false;
+            }
+        }
+        if let Some(ref rule_dir) = self.working_dir {
+            if !cwd.starts_with(rule_dir.as_str()) {
+                return false;
+            }
+        }
+        self.compiled().is_ok_and(|re| re.is_match(command))
+    }
+}
+
+/// What a proposed shell command resolved to after evaluating `command_rules` in order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleDecision {
+    Allow,
+    Deny { pattern: String },
+    Confirm,
+}
+
+/// First-match-wins evaluation; default-deny once `rules` is non-empty, so a typo'd pattern
+/// can't silently fall through to "allow everything" the way an always-false unanchored regex
+/// would. An empty rule list preserves today's behavior (decided solely by `shell: true/false`).
+pub fn evaluate_command_rules(
+    rules: &[CommandRule],
+    command: &str,
+    cwd: &str,
+    target_user: Option<&str>,
+) -> RuleDecision {
+    if rules.is_empty() {
+        return RuleDecision::Allow;
+    }
+    for rule in rules {
+        if rule.matches(command, cwd, target_user) {
+            return match rule.action {
+                RuleAction::Allow => RuleDecision::Allow,
+                RuleAction::Deny => RuleDecision::Deny { pattern: rule.pattern.clone() },
+                RuleAction::Confirm => RuleDecision::Confirm,
+            };
+        }
+    }
+    RuleDecision::Deny { pattern: "<no rule matched, default-deny>".to_string() }
+}
+
+/// Validate every rule's pattern compiles and is anchored, so a malformed or unanchored rule
+/// fails loudly at config-load time instead of quietly never matching.
+pub fn validate_command_rules(rules: &[CommandRule]) -> Result<()> {
+    for rule in rules {
+        if !rule.pattern.starts_with('^') {
+            anyhow::bail!(
+                "command_rules pattern '{}' must start with '^' (unanchored patterns can silently under-match)",
+                rule.pattern
+            );
+        }
+        rule.compiled()?;
+    }
+    Ok(())
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -52,6 +239,12 @@ impl Default for Config {
                 api_url: "https://api.openai.com/v1".to_string(),
                 model: "gpt-4".to_string(),
                 max_tokens: 4096,
+                project_id: None,
+                location: None,
+                adc_file: None,
+                max_retries: default_max_retries(),
+                fallback_models: vec![],
+                max_steps: default_max_steps(),
             },
             security: SecurityConfig {
                 allow_absolute_paths: false,
@@ -65,8 +258,17 @@ impl Default for Config {
                     fs_listdir: true,
                     shell: true,
                 },
+                run_as_user: None,
+                command_rules: vec![],
+                max_parallelism: 0,
             },
             whitelist: vec![],
+            blocklist: vec![],
+            ignore_patterns: vec![],
+            custom_tools: vec![],
+            aliases: BTreeMap::new(),
+            env: BTreeMap::new(),
+            interactive: InteractiveConfig::default(),
         }
     }
 }
@@ -77,8 +279,30 @@ pub struct ConfigManager {
     env_path: PathBuf,
     ignore_path: PathBuf,
     system_prompt_path: PathBuf,
+    history_path: PathBuf,
 }
 
+/// Every static dotted key `get_config_value`/`set_config_value` understands, for shell
+/// completion — `aliases.*`/`env.*` keys are dynamic and completed from the user's own config.
+pub const CONFIG_KEYS: &[&str] = &[
+    "llm.max_tokens",
+    "llm.model",
+    "llm.provider",
+    "llm.api_url",
+    "llm.project_id",
+    "llm.location",
+    "llm.adc_file",
+    "llm.max_retries",
+    "llm.fallback_models",
+    "llm.max_steps",
+    "security.allow_absolute_paths",
+    "security.allow_config_path_access",
+    "security.run_as_user",
+    "security.command_rules",
+    "security.max_parallelism",
+    "interactive.history_size",
+];
+
 impl ConfigManager {
     pub fn new() -> Result<Self> {
         let home = dirs::home_dir().context("Could not find home directory")?;
@@ -87,6 +311,7 @@ impl ConfigManager {
         let env_path = config_dir.join("tokens.env");
         let ignore_path = config_dir.join(".aishignore");
         let system_prompt_path = config_dir.join("system_prompt.txt");
+        let history_path = config_dir.join("history");
 
         Ok(Self {
             config_dir,
@@ -94,6 +319,7 @@ impl ConfigManager {
             env_path,
             ignore_path,
             system_prompt_path,
+            history_path,
         })
     }
 
@@ -105,13 +331,35 @@ impl ConfigManager {
         &self.config_dir
     }
 
+    pub fn get_history_path(&self) -> &PathBuf {
+        &self.history_path
+    }
+
     pub fn load_config(&self) -> Result<Config> {
         let content =
             fs::read_to_string(&self.config_path).context("Failed to read config file")?;
         let config: Config = toml::from_str(&content).context("Failed to parse config file")?;
+        validate_command_rules(&config.security.command_rules)?;
         Ok(config)
     }
 
+    /// Resolve the effective config for the current directory: built-in defaults, the global
+    /// `~/.aish/config.toml`, a repo-root `.aish.toml`, the nearest per-directory `.aish.toml`,
+    /// then `AISH_*` env overrides, deep-merged so a partial overlay never drops keys it omits.
+    /// Unlike `load_config`, this never fails just because the global file is missing — it
+    /// falls back through the same precedence chain down to `Config::default()`.
+    pub fn load_layered_config(&self) -> Result<crate::config_layers::LayeredConfig> {
+        let cwd = std::env::current_dir().context("Failed to determine current directory")?;
+        self.load_layered_config_for(&cwd)
+    }
+
+    /// Same as `load_layered_config`, but resolved for an explicit directory rather than aish's
+    /// own process cwd — used to re-resolve the layered config (and, from it, a fresh
+    /// `SecurityValidator`) as the tracked shell session cwd moves between directories.
+    pub fn load_layered_config_for(&self, dir: &std::path::Path) -> Result<crate::config_layers::LayeredConfig> {
+        crate::config_layers::load_layered(&self.config_path, dir)
+    }
+
     pub fn save_config(&self, config: &Config) -> Result<()> {
         fs::create_dir_all(&self.config_dir)?;
         let content = toml::to_string_pretty(config)?;
@@ -147,12 +395,38 @@ impl ConfigManager {
             ["llm", "model"] => Ok(config.llm.model),
             ["llm", "provider"] => Ok(config.llm.provider),
             ["llm", "api_url"] => Ok(config.llm.api_url),
+            ["llm", "project_id"] => Ok(config.llm.project_id.unwrap_or_default()),
+            ["llm", "location"] => Ok(config.llm.location.unwrap_or_default()),
+            ["llm", "adc_file"] => Ok(config.llm.adc_file.unwrap_or_default()),
+            ["llm", "max_retries"] => Ok(config.llm.max_retries.to_string()),
+            ["llm", "fallback_models"] => Ok(config.llm.fallback_models.join(",")),
+            ["llm", "max_steps"] => Ok(config.llm.max_steps.to_string()),
             ["security", "allow_absolute_paths"] => {
                 Ok(config.security.allow_absolute_paths.to_string())
             }
             ["security", "allow_config_path_access"] => {
                 Ok(config.security.allow_config_path_access.to_string())
             }
+            ["security", "run_as_user"] => Ok(config.security.run_as_user.unwrap_or_default()),
+            ["security", "max_parallelism"] => Ok(config.security.max_parallelism.to_string()),
+            ["interactive", "history_size"] => Ok(config.interactive.history_size.to_string()),
+            ["security", "command_rules"] => Ok(config
+                .security
+                .command_rules
+                .iter()
+                .map(format_command_rule)
+                .collect::<Vec<_>>()
+                .join(";")),
+            ["aliases", name] => config
+                .aliases
+                .get(*name)
+                .cloned()
+                .with_context(|| format!("Unknown alias: {}", name)),
+            ["env", name] => config
+                .env
+                .get(*name)
+                .cloned()
+                .with_context(|| format!("Unknown env var: {}", name)),
             _ => anyhow::bail!("Unknown config key: {}", key),
         }
     }
@@ -166,12 +440,45 @@ impl ConfigManager {
             ["llm", "model"] => config.llm.model = value.to_string(),
             ["llm", "provider"] => config.llm.provider = value.to_string(),
             ["llm", "api_url"] => config.llm.api_url = value.to_string(),
+            ["llm", "project_id"] => config.llm.project_id = Some(value.to_string()),
+            ["llm", "location"] => config.llm.location = Some(value.to_string()),
+            ["llm", "adc_file"] => config.llm.adc_file = Some(value.to_string()),
+            ["llm", "max_retries"] => config.llm.max_retries = value.parse()?,
+            ["llm", "fallback_models"] => {
+                config.llm.fallback_models =
+                    value.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+            }
+            ["llm", "max_steps"] => config.llm.max_steps = value.parse()?,
             ["security", "allow_absolute_paths"] => {
                 config.security.allow_absolute_paths = value.parse()?
             }
             ["security", "allow_config_path_access"] => {
                 config.security.allow_config_path_access = value.parse()?
             }
+            ["security", "run_as_user"] => {
+                config.security.run_as_user =
+                    if value.is_empty() { None } else { Some(value.to_string()) }
+            }
+            ["security", "max_parallelism"] => config.security.max_parallelism = value.parse()?,
+            ["interactive", "history_size"] => config.interactive.history_size = value.parse()?,
+            ["security", "command_rules"] => {
+                let rules: Vec<CommandRule> = if value.is_empty() {
+                    vec![]
+                } else {
+                    value
+                        .split(';')
+                        .map(parse_command_rule)
+                        .collect::<Result<Vec<_>>>()?
+                };
+                validate_command_rules(&rules)?;
+                config.security.command_rules = rules;
+            }
+            ["aliases", name] => {
+                config.aliases.insert(name.to_string(), value.to_string());
+            }
+            ["env", name] => {
+                config.env.insert(name.to_string(), value.to_string());
+            }
             _ => anyhow::bail!("Unknown config key: {}", key),
         }
 
@@ -199,6 +506,44 @@ impl ConfigManager {
     }
 }
 
+/// `pattern|action|target_user|working_dir`, matching the compact single-line form
+/// `get_config_value`/`set_config_value` already use for `fallback_models`.
+fn format_command_rule(rule: &CommandRule) -> String {
+    let action = match rule.action {
+        RuleAction::Allow => "allow",
+        RuleAction::Deny => "deny",
+        RuleAction::Confirm => "confirm",
+    };
+    format!(
+        "{}|{}|{}|{}",
+        rule.pattern,
+        action,
+        rule.target_user.as_deref().unwrap_or(""),
+        rule.working_dir.as_deref().unwrap_or("")
+    )
+}
+
+fn parse_command_rule(entry: &str) -> Result<CommandRule> {
+    let parts: Vec<&str> = entry.split('|').collect();
+    let [pattern, action, target_user, working_dir] = parts.as_slice() else {
+        anyhow::bail!("command_rules entry '{}' must be 'pattern|action|target_user|working_dir'", entry);
+    };
+
+    let action = match *action {
+        "allow" => RuleAction::Allow,
+        "deny" => RuleAction::Deny,
+        "confirm" => RuleAction::Confirm,
+        other => anyhow::bail!("Unknown command rule action '{}' (expected allow/deny/confirm)", other),
+    };
+
+    Ok(CommandRule {
+        pattern: pattern.to_string(),
+        action,
+        target_user: if target_user.is_empty() { None } else { Some(target_user.to_string()) },
+        working_dir: if working_dir.is_empty() { None } else { Some(working_dir.to_string()) },
+    })
+}
+
 fn get_default_system_prompt() -> String {
     include_str!("../data/system_prompt.txt").to_string()
 }
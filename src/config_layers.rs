@@ -0,0 +1,208 @@
+use anyhow::Result;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Per-directory/project config file name, distinct from the global `~/.aish/config.toml`.
+const PROJECT_CONFIG_FILE: &str = ".aish.toml";
+
+/// Dotted config keys that can be overridden by an `AISH_<KEY>` environment variable, using
+/// the same key space `get_config_value`/`set_config_value` understand.
+const ENV_OVERRIDE_KEYS: &[&str] = &[
+    "llm.provider",
+    "llm.api_url",
+    "llm.model",
+    "llm.max_tokens",
+    "llm.project_id",
+    "llm.location",
+    "llm.adc_file",
+    "llm.max_retries",
+    "llm.fallback_models",
+    "security.allow_absolute_paths",
+    "security.allow_config_path_access",
+];
+
+/// The effective `Config` after merging every layer, plus which layer last set each top-level
+/// dotted key (for debugging "why is my model X").
+pub struct LayeredConfig {
+    pub config: Config,
+    pub sources: BTreeMap<String, String>,
+}
+
+/// Resolve the layered config: built-in defaults, then the global `~/.aish/config.toml`, then
+/// a repo-root `.aish.toml` (if `cwd` is inside a git repo), then the nearest per-directory
+/// `.aish.toml` walking up from `cwd` to the repo root, then `AISH_*` environment overrides.
+/// Each file is read at most once even if a layer boundary would otherwise revisit it.
+pub fn load_layered(global_config_path: &Path, cwd: &Path) -> Result<LayeredConfig> {
+    let mut already_read: HashSet<PathBuf> = HashSet::new();
+    let mut sources: BTreeMap<String, String> = BTreeMap::new();
+
+    let mut merged = toml::Value::try_from(&Config::default())?;
+    record_sources(&merged, "builtin-default", &mut sources);
+
+    if let Some(global) = read_once(global_config_path, &mut already_read) {
+        merge_toml(&mut merged, &global);
+        record_sources(&global, "global", &mut sources);
+    }
+
+    let repo_root = find_git_root(cwd);
+
+    if let Some(ref root) = repo_root {
+        let repo_config = root.join(PROJECT_CONFIG_FILE);
+        if let Some(value) = read_once(&repo_config, &mut already_read) {
+            merge_toml(&mut merged, &value);
+            record_sources(&value, "repo-root", &mut sources);
+        }
+    }
+
+    for dir in directories_from(cwd, repo_root.as_deref()) {
+        let candidate = dir.join(PROJECT_CONFIG_FILE);
+        if let Some(value) = read_once(&candidate, &mut already_read) {
+            merge_toml(&mut merged, &value);
+            record_sources(&value, &format!("directory:{}", dir.display()), &mut sources);
+        }
+    }
+
+    apply_env_overrides(&mut merged, &mut sources);
+
+    let config: Config = merged.try_into()?;
+    crate::config::validate_command_rules(&config.security.command_rules)?;
+    Ok(LayeredConfig { config, sources })
+}
+
+/// Directories from `cwd` up to (but not including) `repo_root`, nearest first, so the closest
+/// directory's config overrides the repo root's and is itself overridden by nothing below it.
+fn directories_from(cwd: &Path, repo_root: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let mut current = Some(cwd.to_path_buf());
+    while let Some(dir) = current {
+        if Some(dir.as_path()) == repo_root {
+            break;
+        }
+        dirs.push(dir.clone());
+        current = dir.parent().map(|p| p.to_path_buf());
+        if current.is_none() || repo_root.is_none() && dirs.len() > 64 {
+            break;
+        }
+    }
+    // Nearest-first so callers merge furthest-from-root last (closest directory wins).
+    dirs.reverse();
+    dirs
+}
+
+fn find_git_root(start: &Path) -> Option<PathBuf> {
+    let mut dir = Some(start);
+    while let Some(d) = dir {
+        if d.join(".git").exists() {
+            return Some(d.to_path_buf());
+        }
+        dir = d.parent();
+    }
+    None
+}
+
+fn read_once(path: &Path, already_read: &mut HashSet<PathBuf>) -> Option<toml::Value> {
+    if already_read.contains(path) {
+        return None;
+    }
+    already_read.insert(path.to_path_buf());
+    let content = std::fs::read_to_string(path).ok()?;
+    content.parse::<toml::Value>().ok()
+}
+
+/// Deep-merge `overlay` into `base`: tables merge key-by-key recursively, anything else in
+/// `overlay` replaces the corresponding value in `base` — except `whitelist`/`blocklist`/
+/// `ignore_patterns`, which accumulate across layers (see `merge_additive_list`). A partial
+/// overlay therefore never drops keys it doesn't mention.
+fn merge_toml(base: &mut toml::Value, overlay: &toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base_table), toml::Value::Table(overlay_table)) => {
+            for (key, overlay_value) in overlay_table {
+                match base_table.get_mut(key) {
+                    Some(base_value) if is_additive_list_key(key) => {
+                        merge_additive_list(base_value, overlay_value)
+                    }
+                    Some(base_value) => merge_toml(base_value, overlay_value),
+                    None => {
+                        base_table.insert(key.clone(), overlay_value.clone());
+                    }
+                }
+            }
+        }
+        (base_value, overlay_value) => {
+            *base_value = overlay_value.clone();
+        }
+    }
+}
+
+/// `whitelist`/`blocklist`/`ignore_patterns` should grow as layers get nearer to `cwd` rather
+/// than the nearest layer silently dropping every pattern a further-out layer declared — so a
+/// project's `.aish.toml` can add a pattern without repeating the global config's own list.
+fn is_additive_list_key(key: &str) -> bool {
+    matches!(key, "whitelist" | "blocklist" | "ignore_patterns")
+}
+
+fn merge_additive_list(base: &mut toml::Value, overlay: &toml::Value) {
+    let (toml::Value::Array(base_items), toml::Value::Array(overlay_items)) = (&mut *base, overlay)
+    else {
+        *base = overlay.clone();
+        return;
+    };
+    for item in overlay_items {
+        if !base_items.contains(item) {
+            base_items.push(item.clone());
+        }
+    }
+}
+
+/// Record, for every leaf key a table/value provides, which layer it came from. Keys are
+/// dotted paths (`llm.model`) matching `get_config_value`'s key space.
+fn record_sources(value: &toml::Value, layer: &str, sources: &mut BTreeMap<String, String>) {
+    record_sources_at("", value, layer, sources);
+}
+
+fn record_sources_at(prefix: &str, value: &toml::Value, layer: &str, sources: &mut BTreeMap<String, String>) {
+    if let toml::Value::Table(table) = value {
+        for (key, inner) in table {
+            let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            record_sources_at(&path, inner, layer, sources);
+        }
+    } else if !prefix.is_empty() {
+        sources.insert(prefix.to_string(), layer.to_string());
+    }
+}
+
+fn apply_env_overrides(merged: &mut toml::Value, sources: &mut BTreeMap<String, String>) {
+    for key in ENV_OVERRIDE_KEYS {
+        let env_name = format!("AISH_{}", key.to_uppercase().replace('.', "_"));
+        let Ok(raw_value) = std::env::var(&env_name) else {
+            continue;
+        };
+        set_dotted(merged, key, &raw_value);
+        sources.insert(key.to_string(), format!("env:{}", env_name));
+    }
+}
+
+/// Set a dotted key (`"llm.model"`) on a TOML table, inferring bool/int vs. string from the
+/// existing value at that path when present.
+fn set_dotted(root: &mut toml::Value, dotted_key: &str, raw_value: &str) {
+    let parts: Vec<&str> = dotted_key.split('.').collect();
+    let Some((leaf, parents)) = parts.split_last() else {
+        return;
+    };
+
+    let mut current = root;
+    for part in parents {
+        let toml::Value::Table(table) = current else { return };
+        current = table.entry(part.to_string()).or_insert_with(|| toml::Value::Table(Default::default()));
+    }
+    let toml::Value::Table(table) = current else { return };
+
+    let new_value = match table.get(*leaf) {
+        Some(toml::Value::Boolean(_)) => raw_value.parse().map(toml::Value::Boolean).unwrap_or_else(|_| toml::Value::String(raw_value.to_string())),
+        Some(toml::Value::Integer(_)) => raw_value.parse().map(toml::Value::Integer).unwrap_or_else(|_| toml::Value::String(raw_value.to_string())),
+        _ => toml::Value::String(raw_value.to_string()),
+    };
+    table.insert(leaf.to_string(), new_value);
+}
@@ -1,9 +1,72 @@
 use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::security::SecurityValidator;
 
 pub struct FsOperations;
 
+/// Options for `FsOperations::collect_files`.
+#[derive(Debug, Clone, Default)]
+pub struct CollectOptions {
+    /// Follow directory symlinks instead of skipping them. Cycles are still guarded against via
+    /// canonical-path tracking, so a symlink loop can't cause an infinite walk.
+    pub follow_symlinks: bool,
+    /// Skip paths matched by a root-level `.gitignore`, using the same glob-to-regex translation
+    /// as `SecurityValidator`'s ignore patterns (`*` -> `.*`).
+    pub respect_gitignore: bool,
+    /// If set, only files whose extension (e.g. `.jpeg`) appears in this list are returned.
+    pub allowed_extensions: Option<Vec<String>>,
+}
+
+/// A single operation suitable for `FsOperations::run_batch`. Each variant mirrors one of
+/// `FsOperations`'s own methods, normalized to a uniform `Result<String>` so batch results read
+/// the same way `App::execute_action` already renders a single fs op's result.
+#[derive(Debug, Clone)]
+pub enum FsOp {
+    Read { path: String },
+    Write { path: String, content: String },
+    MakeDir { path: String },
+    ListDir { path: String },
+}
+
+impl FsOp {
+    fn execute(&self) -> Result<String> {
+        match self {
+            FsOp::Read { path } => FsOperations::read_file(path),
+            FsOp::Write { path, content } => {
+                FsOperations::write_file(path, content)?;
+                Ok("File written successfully".to_string())
+            }
+            FsOp::MakeDir { path } => {
+                FsOperations::make_dir(path)?;
+                Ok("Directory created successfully".to_string())
+            }
+            FsOp::ListDir { path } => {
+                FsOperations::list_dir(path).map(|entries| entries.join("\n"))
+            }
+        }
+    }
+
+    /// Writes and makedirs to the same parent directory race on the `create_dir_all` call in
+    /// `write_file`, so they're grouped by canonical parent and serialized; reads and `list_dir`
+    /// never conflict with anything and always get their own group.
+    fn conflict_group(&self, index: usize) -> String {
+        match self {
+            FsOp::Write { path, .. } | FsOp::MakeDir { path } => {
+                let parent = Path::new(path).parent().unwrap_or_else(|| Path::new("."));
+                fs::canonicalize(parent)
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|_| parent.to_string_lossy().to_string())
+            }
+            FsOp::Read { .. } | FsOp::ListDir { .. } => format!("__independent_{}", index),
+        }
+    }
+}
+
 impl FsOperations {
     pub fn read_file(path: &str) -> Result<String> {
         let path = PathBuf::from(path);
@@ -38,4 +101,196 @@ impl FsOperations {
 
         Ok(result)
     }
+
+    /// Run `ops` concurrently on a thread pool sized by `max_parallelism` (or `num_cpus::get()`
+    /// when `0`), preserving input order in the returned results and collecting each op's own
+    /// error without aborting the rest of the batch. Reads and `list_dir` always parallelize;
+    /// writes/makedirs that share a parent directory are serialized to avoid racing
+    /// `create_dir_all` in `write_file`.
+    pub fn run_batch(ops: Vec<FsOp>, max_parallelism: usize) -> Vec<Result<String>> {
+        if ops.is_empty() {
+            return Vec::new();
+        }
+
+        let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, op) in ops.iter().enumerate() {
+            groups.entry(op.conflict_group(index)).or_default().push(index);
+        }
+
+        let num_threads = if max_parallelism > 0 {
+            max_parallelism
+        } else {
+            num_cpus::get()
+        }
+        .max(1);
+
+        let pool = threadpool::ThreadPool::new(num_threads);
+        let results: Arc<Mutex<Vec<Option<Result<String>>>>> =
+            Arc::new(Mutex::new((0..ops.len()).map(|_| None).collect()));
+        let ops = Arc::new(ops);
+
+        for indices in groups.into_values() {
+            let results = Arc::clone(&results);
+            let ops = Arc::clone(&ops);
+            pool.execute(move || {
+                for index in indices {
+                    let result = ops[index].execute();
+                    results.lock().unwrap()[index] = Some(result);
+                }
+            });
+        }
+
+        pool.join();
+
+        Arc::try_unwrap(results)
+            .expect("all worker threads finished after pool.join()")
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.unwrap_or_else(|| Err(anyhow::anyhow!("operation did not complete"))))
+            .collect()
+    }
+
+    /// Recursively walk `root` and return every file found, as paths relative to `root`, sorted
+    /// for stable output. `security` is consulted for every candidate file so blocked extensions,
+    /// ignore patterns, and absolute-path/config-dir rules are enforced exactly as they would be
+    /// for any other fs operation — a blocked file never makes it into the returned set even if
+    /// `opts.allowed_extensions` would otherwise let it through.
+    pub fn collect_files(
+        root: &str,
+        opts: &CollectOptions,
+        security: &SecurityValidator,
+    ) -> Result<Vec<String>> {
+        let root_path = PathBuf::from(root);
+        let gitignore_patterns = if opts.respect_gitignore {
+            Self::load_gitignore_patterns(&root_path)
+        } else {
+            Vec::new()
+        };
+
+        let mut visited = HashSet::new();
+        let mut results = Vec::new();
+        Self::walk_dir(
+            &root_path,
+            &root_path,
+            opts,
+            &gitignore_patterns,
+            security,
+            &mut visited,
+            &mut results,
+        )?;
+
+        results.sort();
+        Ok(results)
+    }
+
+    fn load_gitignore_patterns(root: &Path) -> Vec<Regex> {
+        let Ok(content) = fs::read_to_string(root.join(".gitignore")) else {
+            return Vec::new();
+        };
+
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|pattern| Regex::new(&pattern.replace('*', ".*")).ok())
+            .collect()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn walk_dir(
+        root: &Path,
+        dir: &Path,
+        opts: &CollectOptions,
+        gitignore_patterns: &[Regex],
+        security: &SecurityValidator,
+        visited: &mut HashSet<PathBuf>,
+        results: &mut Vec<String>,
+    ) -> Result<()> {
+        let canonical_dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        if !visited.insert(canonical_dir) {
+            // Already walked this directory via another path (a symlink cycle) — stop here.
+            return Ok(());
+        }
+
+        let entries =
+            fs::read_dir(dir).with_context(|| format!("Failed to list directory: {:?}", dir))?;
+
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+
+            if gitignore_patterns.iter().any(|p| p.is_match(&relative)) {
+                continue;
+            }
+
+            if file_type.is_symlink() {
+                if !opts.follow_symlinks {
+                    continue;
+                }
+                match fs::metadata(&path) {
+                    Ok(meta) if meta.is_dir() => {
+                        Self::walk_dir(
+                            root,
+                            &path,
+                            opts,
+                            gitignore_patterns,
+                            security,
+                            visited,
+                            results,
+                        )?;
+                    }
+                    Ok(_) => Self::push_if_allowed(&path, &relative, opts, security, results),
+                    Err(_) => continue, // broken symlink
+                }
+                continue;
+            }
+
+            if file_type.is_dir() {
+                Self::walk_dir(
+                    root,
+                    &path,
+                    opts,
+                    gitignore_patterns,
+                    security,
+                    visited,
+                    results,
+                )?;
+            } else {
+                Self::push_if_allowed(&path, &relative, opts, security, results);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push_if_allowed(
+        path: &Path,
+        relative: &str,
+        opts: &CollectOptions,
+        security: &SecurityValidator,
+        results: &mut Vec<String>,
+    ) {
+        if let Some(allowed) = &opts.allowed_extensions {
+            let matches = path
+                .extension()
+                .map(|ext| allowed.iter().any(|a| a.trim_start_matches('.') == ext))
+                .unwrap_or(false);
+            if !matches {
+                return;
+            }
+        }
+
+        if security.validate_path(&path.to_string_lossy()).is_err() {
+            return;
+        }
+
+        results.push(relative.to_string());
+    }
 }
@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// Persisted interactive-mode prompt history: a plain newline-delimited file under the config
+/// dir, loaded once at session start and appended to as each non-empty, non-`quit`/`exit` line is
+/// submitted. Capped at `max_size` entries with oldest-eviction; `0` means unbounded.
+pub struct History {
+    path: PathBuf,
+    max_size: usize,
+    entries: Vec<String>,
+}
+
+impl History {
+    pub fn load(path: PathBuf, max_size: usize) -> Self {
+        let entries = fs::read_to_string(&path)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut history = Self {
+            path,
+            max_size,
+            entries,
+        };
+        history.trim();
+        history
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Append `line` and persist it, evicting the oldest entry first if `max_size` is exceeded.
+    pub fn push(&mut self, line: &str) -> Result<()> {
+        self.entries.push(line.to_string());
+        self.trim();
+        self.save()
+    }
+
+    fn trim(&mut self) {
+        if self.max_size == 0 {
+            return;
+        }
+        while self.entries.len() > self.max_size {
+            self.entries.remove(0);
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, self.entries.join("\n") + "\n")
+            .with_context(|| format!("Failed to write history file: {:?}", self.path))
+    }
+
+    /// Resolve a `!!` (last entry) or `!n` (1-indexed entry) re-run expression against the
+    /// current history. Returns `None` if `input` isn't a bang expression or doesn't resolve.
+    pub fn resolve_bang(&self, input: &str) -> Option<String> {
+        if input == "!!" {
+            return self.entries.last().cloned();
+        }
+
+        let n: usize = input.strip_prefix('!')?.parse().ok()?;
+        self.entries.get(n.checked_sub(1)?).cloned()
+    }
+}
@@ -1,17 +1,23 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use clap_complete::Shell;
 use colored::*;
 
 mod cli;
 mod config;
+mod config_layers;
 mod fs_ops;
+mod history;
 mod llm;
+mod run_as;
 mod security;
 mod shell;
+mod shell_session;
+mod ui;
+mod watch;
 
 use cli::app::App;
 use config::{Config, ConfigManager};
-use inquire::Text;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::signal;
@@ -31,6 +37,20 @@ struct Cli {
     #[arg(short, long)]
     interactive: bool,
 
+    /// Watch the workspace for file changes and re-run the agent on each debounced batch
+    /// (e.g. "keep fixing the failing test until it passes")
+    #[arg(long)]
+    watch: bool,
+
+    /// Scriptable output: no colors, no box-drawing, bare `field: value` lines
+    /// (also honors the NO_COLOR and AISH_PLAIN/AISH_PLAINEXCEPT env vars)
+    #[arg(long)]
+    plain: bool,
+
+    /// Output format for config, results, and errors
+    #[arg(long, value_enum, default_value = "pretty")]
+    format: ui::OutputFormat,
+
     /// The prompt to execute
     #[arg(trailing_var_arg = true)]
     prompt: Vec<String>,
@@ -47,11 +67,38 @@ enum Commands {
         /// Value to set
         value: Option<String>,
     },
+    /// Generate a shell completion script
+    Completions {
+        /// Shell to generate completions for (bash, zsh, fish, powershell, elvish)
+        shell: Shell,
+    },
+    /// Manage security policy (whitelist, blocklist, blocked extensions, operation permissions)
+    Policy {
+        #[command(subcommand)]
+        action: cli::policy::PolicyCommand,
+    },
 }
 
+/// Rust resets `SIGPIPE` to `SIG_IGN` on startup, which turns a closed downstream pipe (e.g.
+/// `aish config | head -1`) into an `io::ErrorKind::BrokenPipe` that panics the next time
+/// `println!` tries to write to it. Restoring the default disposition makes a broken stdout
+/// pipe kill the process silently instead, the same way `bat`/`cargo`/most C-derived CLIs behave.
+#[cfg(unix)]
+fn reset_sigpipe() {
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+}
+
+#[cfg(not(unix))]
+fn reset_sigpipe() {}
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    reset_sigpipe();
     let cli = Cli::parse();
+    ui::init_plain(cli.plain);
+    ui::init_format(cli.format);
     let config_manager = ConfigManager::new()?;
 
     match cli.command {
@@ -61,22 +108,50 @@ async fn main() -> Result<()> {
         Some(Commands::Config { key, value }) => {
             handle_config_command(&config_manager, key, value)?;
         }
+        Some(Commands::Completions { shell }) => {
+            cli::completions::print_completions::<Cli>(shell)?;
+        }
+        Some(Commands::Policy { action }) => {
+            cli::policy::run(&config_manager, action)?;
+        }
         None => {
             if !config_manager.is_initialized() {
                 eprintln!("Configuration not found. Please run: aish init");
                 std::process::exit(1);
             }
 
-            let config = config_manager.load_config()?;
+            let config = config_manager.load_layered_config()?.config;
             let prompt = cli.prompt.join(" ");
 
-            if cli.interactive {
+            if cli.watch {
                 let initial_prompt = if prompt.is_empty() {
                     None
                 } else {
                     Some(prompt)
                 };
-                run_interactive_mode(config, cli.accept_all, initial_prompt).await?;
+
+                let mut app = if let Some(p) = initial_prompt.clone() {
+                    App::new(config, p, cli.accept_all)?
+                } else {
+                    App::new_empty(config, cli.accept_all)?
+                };
+
+                if initial_prompt.is_some() {
+                    app.run().await?;
+                }
+
+                if let Err(e) = app.run_watching().await {
+                    ui::print_error(&e);
+                    std::process::exit(1);
+                }
+            } else if cli.interactive {
+                let initial_prompt = if prompt.is_empty() {
+                    None
+                } else {
+                    Some(prompt)
+                };
+                let history_path = config_manager.get_history_path().clone();
+                run_interactive_mode(config, cli.accept_all, initial_prompt, history_path).await?;
             } else {
                 if prompt.is_empty() {
                     eprintln!("Usage: aish <prompt>");
@@ -85,7 +160,10 @@ async fn main() -> Result<()> {
                 }
 
                 let mut app = App::new(config, prompt, cli.accept_all)?;
-                app.run().await?;
+                if let Err(e) = app.run().await {
+                    ui::print_error(&e);
+                    std::process::exit(1);
+                }
             }
         }
     }
@@ -101,24 +179,45 @@ fn handle_config_command(
     if let Some(k) = key {
         if let Some(v) = value {
             config_manager.set_config_value(&k, &v)?;
-            println!(
-                "{} Set {} = {}",
-                "✓".green(),
-                k.bright_cyan(),
-                v.bright_yellow()
-            );
+            if ui::format::is_json() {
+                println!("{}", serde_json::json!({ "key": k, "value": v }));
+            } else if ui::plain_info().suppress_boxes {
+                println!("{}: {}", k, v);
+            } else {
+                println!(
+                    "{} Set {} = {}",
+                    "✓".green(),
+                    k.bright_cyan(),
+                    v.bright_yellow()
+                );
+            }
         } else {
             let val = config_manager.get_config_value(&k)?;
-            println!("{} = {}", k.bright_cyan(), val.bright_yellow());
+            if ui::format::is_json() {
+                println!("{}", serde_json::json!({ "key": k, "value": val }));
+            } else if ui::plain_info().suppress_boxes {
+                println!("{}: {}", k, val);
+            } else {
+                println!("{} = {}", k.bright_cyan(), val.bright_yellow());
+            }
         }
     } else {
         let config = config_manager.load_config()?;
-        print_config_pretty(&config);
+        if ui::format::is_json() {
+            println!("{}", serde_json::to_string_pretty(&config)?);
+        } else {
+            print_config_pretty(&config);
+        }
     }
     Ok(())
 }
 
 fn print_config_pretty(config: &Config) {
+    if ui::plain_info().suppress_boxes {
+        print_config_plain(config);
+        return;
+    }
+
     println!(
         "{}",
         "╔════════════════════════════════════════════════════════╗".bright_black()
@@ -257,6 +356,57 @@ fn print_config_pretty(config: &Config) {
     }
 }
 
+/// Bare `field: value` counterpart to `print_config_pretty`, used in plain mode — every field
+/// shown in the boxed layout still appears here, one per line, with no borders or color.
+fn print_config_plain(config: &Config) {
+    println!("llm.provider: {}", config.llm.provider);
+    println!("llm.api_url: {}", config.llm.api_url);
+    println!("llm.model: {}", config.llm.model);
+    println!("llm.max_tokens: {}", config.llm.max_tokens);
+
+    println!(
+        "security.allow_absolute_paths: {}",
+        config.security.allow_absolute_paths
+    );
+    println!(
+        "security.allow_config_path_access: {}",
+        config.security.allow_config_path_access
+    );
+
+    for ext in &config.security.blocked_extensions {
+        println!("security.blocked_extensions: {}", ext);
+    }
+
+    println!(
+        "security.allowed_operations.fs_makedir: {}",
+        config.security.allowed_operations.fs_makedir
+    );
+    println!(
+        "security.allowed_operations.fs_makefile: {}",
+        config.security.allowed_operations.fs_makefile
+    );
+    println!(
+        "security.allowed_operations.fs_writefile: {}",
+        config.security.allowed_operations.fs_writefile
+    );
+    println!(
+        "security.allowed_operations.fs_readfile: {}",
+        config.security.allowed_operations.fs_readfile
+    );
+    println!(
+        "security.allowed_operations.fs_listdir: {}",
+        config.security.allowed_operations.fs_listdir
+    );
+    println!(
+        "security.allowed_operations.shell: {}",
+        config.security.allowed_operations.shell
+    );
+
+    for item in &config.whitelist {
+        println!("whitelist: {}", item);
+    }
+}
+
 fn format_bool(value: bool) -> ColoredString {
     if value {
         "true".bright_green()
@@ -269,15 +419,30 @@ async fn run_interactive_mode(
     config: Config,
     accept_all: bool,
     initial_prompt: Option<String>,
+    history_path: std::path::PathBuf,
 ) -> Result<()> {
     use colored::*;
+    use history::History;
+    use rustyline::error::ReadlineError;
+    use rustyline::DefaultEditor;
 
     println!(
         "{}",
         "→ Interactive mode started. Type 'quit' or 'exit' to end, or press Ctrl+C".bright_cyan()
     );
+    println!(
+        "{}",
+        "  Use ↑/↓ for history, '/history' to list it, '!!'/'!n' to re-run a prior prompt"
+            .bright_black()
+    );
     println!();
 
+    let mut history = History::load(history_path, config.interactive.history_size);
+    let mut editor = DefaultEditor::new().context("Failed to initialize interactive prompt")?;
+    for entry in history.entries() {
+        let _ = editor.add_history_entry(entry.as_str());
+    }
+
     // Initialize app
     let mut app = if let Some(prompt) = initial_prompt.clone() {
         // If initial prompt provided, use it
@@ -310,13 +475,11 @@ async fn run_interactive_mode(
             break;
         }
 
-        let prompt = Text::new("aish>")
-            .with_help_message("Enter your command or 'quit'/'exit' to exit")
-            .prompt();
+        let readline = editor.readline("aish> ");
 
-        match prompt {
-            Ok(p) => {
-                let p = p.trim();
+        match readline {
+            Ok(line) => {
+                let p = line.trim();
                 if p.is_empty() {
                     continue;
                 }
@@ -329,8 +492,43 @@ async fn run_interactive_mode(
                     break;
                 }
 
+                if p == "/history" {
+                    print_history(&history);
+                    continue;
+                }
+
+                // Resolve `!!`/`!n` re-run expressions against the persisted history before
+                // anything else, so the re-run itself is what gets recorded (not the bang syntax).
+                let resolved = if p.starts_with('!') {
+                    match history.resolve_bang(p) {
+                        Some(resolved) => {
+                            println!("{}", format!("→ Re-running: {}", resolved).bright_black());
+                            resolved
+                        }
+                        None => {
+                            eprintln!(
+                                "{} {}",
+                                "×".bright_red(),
+                                format!("No matching history entry for '{}'", p).bright_red()
+                            );
+                            continue;
+                        }
+                    }
+                } else {
+                    p.to_string()
+                };
+
+                let _ = editor.add_history_entry(resolved.as_str());
+                if let Err(e) = history.push(&resolved) {
+                    eprintln!(
+                        "{} {}",
+                        "×".bright_red(),
+                        format!("Failed to persist history: {}", e).bright_red()
+                    );
+                }
+
                 // Add user message and run
-                app.add_user_message(p.to_string());
+                app.add_user_message(resolved);
                 match app.run().await {
                     Ok(_) => {
                         // Continue loop for next prompt
@@ -345,12 +543,13 @@ async fn run_interactive_mode(
                     }
                 }
             }
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => {
+                println!();
+                println!("{}", "→ Exiting interactive mode...".bright_cyan());
+                break;
+            }
             Err(_) => {
-                // User cancelled with Esc or similar, or Ctrl+C
-                if ctrl_c_pressed.load(Ordering::Relaxed) {
-                    println!();
-                    println!("{}", "→ Exiting interactive mode...".bright_cyan());
-                }
+                // Unexpected terminal error — stop rather than loop forever.
                 break;
             }
         }
@@ -358,3 +557,16 @@ async fn run_interactive_mode(
 
     Ok(())
 }
+
+fn print_history(history: &history::History) {
+    use colored::*;
+
+    if history.entries().is_empty() {
+        println!("{}", "(history is empty)".bright_black());
+        return;
+    }
+
+    for (index, entry) in history.entries().iter().enumerate() {
+        println!("{} {}", format!("{:>4}", index + 1).bright_black(), entry);
+    }
+}
@@ -0,0 +1,146 @@
+//! Unix-only user resolution and privilege dropping for `ShellExecutor`'s run-as-user option.
+//! Only makes sense when aish itself is running as root, which is why the feature is gated
+//! behind `SecurityConfig::run_as_user` and confirmed in the init wizard.
+#![cfg(unix)]
+
+use anyhow::{bail, Context, Result};
+use std::ffi::CString;
+use std::process::Command;
+
+/// The pieces of a passwd entry `ShellExecutor` needs to drop into a different user's shell.
+pub struct UserIdentity {
+    pub username: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub groups: Vec<u32>,
+    pub home: String,
+    pub shell: String,
+}
+
+/// Resolve `username` to its uid/gid/supplementary groups/home/shell via `libc`, falling back
+/// to parsing `id -u`/`id -g`/`id -G` plus `getent passwd` output if the `libc` lookup fails
+/// (e.g. nss backends `libc` can't see, like some container base images).
+pub fn resolve_user(username: &str) -> Result<UserIdentity> {
+    if let Some(identity) = resolve_via_libc(username) {
+        return Ok(identity);
+    }
+    resolve_via_id_command(username)
+}
+
+fn resolve_via_libc(username: &str) -> Option<UserIdentity> {
+    let c_username = CString::new(username).ok()?;
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buf = vec![0i8; 16384];
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+
+    let rc = unsafe {
+        libc::getpwnam_r(
+            c_username.as_ptr(),
+            &mut passwd,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        )
+    };
+    if rc != 0 || result.is_null() {
+        return None;
+    }
+
+    let uid = passwd.pw_uid;
+    let gid = passwd.pw_gid;
+    let home = unsafe { std::ffi::CStr::from_ptr(passwd.pw_dir) }.to_string_lossy().to_string();
+    let shell = unsafe { std::ffi::CStr::from_ptr(passwd.pw_shell) }.to_string_lossy().to_string();
+
+    let mut ngroups: libc::c_int = 32;
+    let mut groups = vec![0 as libc::gid_t; ngroups as usize];
+    let rc = unsafe {
+        libc::getgrouplist(
+            c_username.as_ptr(),
+            gid as libc::gid_t,
+            groups.as_mut_ptr(),
+            &mut ngroups,
+        )
+    };
+    if rc == -1 {
+        // Buffer was too small; retry with the size getgrouplist reported it needed.
+        groups = vec![0 as libc::gid_t; ngroups as usize];
+        unsafe {
+            libc::getgrouplist(
+                c_username.as_ptr(),
+                gid as libc::gid_t,
+                groups.as_mut_ptr(),
+                &mut ngroups,
+            );
+        }
+    }
+    groups.truncate(ngroups.max(0) as usize);
+
+    Some(UserIdentity {
+        username: username.to_string(),
+        uid,
+        gid,
+        groups: groups.into_iter().map(|g| g as u32).collect(),
+        home,
+        shell: if shell.is_empty() { "/bin/sh".to_string() } else { shell },
+    })
+}
+
+fn resolve_via_id_command(username: &str) -> Result<UserIdentity> {
+    let uid = run_id(&["-u", username])?.parse().context("Failed to parse uid from `id -u`")?;
+    let gid = run_id(&["-g", username])?.parse().context("Failed to parse gid from `id -g`")?;
+    let groups = run_id(&["-G", username])?
+        .split_whitespace()
+        .filter_map(|g| g.parse().ok())
+        .collect();
+
+    let passwd_line = std::fs::read_to_string("/etc/passwd")
+        .unwrap_or_default()
+        .lines()
+        .find(|line| line.split(':').next() == Some(username))
+        .map(str::to_string);
+
+    let (home, shell) = match &passwd_line {
+        Some(line) => {
+            let fields: Vec<&str> = line.split(':').collect();
+            (
+                fields.get(5).unwrap_or(&"/").to_string(),
+                fields.get(6).unwrap_or(&"/bin/sh").to_string(),
+            )
+        }
+        None => ("/".to_string(), "/bin/sh".to_string()),
+    };
+
+    Ok(UserIdentity { username: username.to_string(), uid, gid, groups, home, shell })
+}
+
+fn run_id(args: &[&str]) -> Result<String> {
+    let output = Command::new("id").args(args).output().context("Failed to run `id`")?;
+    if !output.status.success() {
+        bail!("`id {}` failed: user not found", args.join(" "));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Drop the *current process's* privileges to `identity`, strictly in the order
+/// `setgroups` -> `setgid` -> `setuid` so the process can never regain elevated privileges.
+/// Intended to run inside a `pre_exec` hook (i.e. after `fork`, before `exec`) so only the
+/// spawned child is affected, not aish itself.
+pub fn drop_privileges(identity: &UserIdentity) -> std::io::Result<()> {
+    let groups: Vec<libc::gid_t> = identity.groups.iter().map(|g| *g as libc::gid_t).collect();
+    let rc = unsafe { libc::setgroups(groups.len(), groups.as_ptr()) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    let rc = unsafe { libc::setgid(identity.gid as libc::gid_t) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    let rc = unsafe { libc::setuid(identity.uid as libc::uid_t) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
@@ -2,30 +2,48 @@ use anyhow::{bail, Result};
 use regex::Regex;
 use std::path::PathBuf;
 
-use crate::config::{Config, ConfigManager};
+use crate::config::{evaluate_command_rules, Config, ConfigManager, RuleDecision};
 
 pub struct SecurityValidator {
     config: Config,
     config_manager: ConfigManager,
     ignore_patterns: Vec<Regex>,
+    blocklist: Vec<Regex>,
 }
 
 impl SecurityValidator {
     pub fn new(config: Config) -> Result<Self> {
         let config_manager = ConfigManager::new()?;
-        let patterns = config_manager.load_ignore_patterns();
+
+        // The global `.aishignore` file plus whatever directory/repo-root `.aish.toml` layers
+        // additively contributed to `config.ignore_patterns` (see `is_additive_list_key`) — a
+        // directory-local pattern adds to the global list rather than replacing it.
+        let mut patterns = config_manager.load_ignore_patterns();
+        patterns.extend(config.ignore_patterns.iter().cloned());
         let ignore_patterns: Vec<Regex> = patterns
             .iter()
             .filter_map(|p| Regex::new(&p.replace("*", ".*")).ok())
             .collect();
+        let blocklist: Vec<Regex> = config
+            .blocklist
+            .iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
 
         Ok(Self {
             config,
             config_manager,
             ignore_patterns,
+            blocklist,
         })
     }
 
+    /// Compiled `.aishignore`/config ignore patterns, for callers (e.g. the file watcher) that
+    /// need to reuse the same "should this path be treated as noise" check `validate_path` uses.
+    pub fn ignore_patterns(&self) -> &[Regex] {
+        &self.ignore_patterns
+    }
+
     pub fn validate_path(&self, path: &str) -> Result<()> {
         let path = PathBuf::from(path);
 
@@ -79,6 +97,18 @@ impl SecurityValidator {
         Ok(())
     }
 
+    /// Evaluate `command` against `security.command_rules` for the given working directory and
+    /// (optional) run-as-user target. Callers must reject the command on `RuleDecision::Deny`
+    /// and gate it behind an interactive confirmation on `RuleDecision::Confirm`.
+    pub fn evaluate_command(&self, command: &str, cwd: &str) -> RuleDecision {
+        evaluate_command_rules(
+            &self.config.security.command_rules,
+            command,
+            cwd,
+            self.config.security.run_as_user.as_deref(),
+        )
+    }
+
     pub fn is_whitelisted(&self, command: &str) -> bool {
         self.config.whitelist.iter().any(|pattern| {
             if let Ok(regex) = Regex::new(pattern) {
@@ -88,4 +118,10 @@ impl SecurityValidator {
             }
         })
     }
+
+    /// Unconditional denylist check, evaluated independently of `accept_all`/whitelist — a
+    /// matching command is never run, not even auto-approved under `--accept-all`.
+    pub fn is_blocked(&self, command: &str) -> bool {
+        self.blocklist.iter().any(|pattern| pattern.is_match(command))
+    }
 }
@@ -1,17 +1,82 @@
-use anyhow::Result;
-use std::io::{self, BufRead, BufReader, Write};
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
 use std::process::{Command, Stdio};
 use std::thread;
+use std::time::Duration;
+
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use regex::Regex;
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+#[cfg(unix)]
+use crate::run_as::{self, UserIdentity};
+
+/// First-token program names known to need a real controlling terminal (editors, pagers,
+/// remote shells, interactive REPLs/prompts) rather than the fast piped-line path.
+const INTERACTIVE_PROGRAMS: &[&str] = &[
+    "vim", "vi", "nvim", "nano", "emacs", "top", "htop", "less", "more", "man", "ssh", "mosh",
+    "mysql", "psql", "sqlite3", "fzf", "tmux", "screen", "python3", "python", "node", "irb",
+    "ftp", "sftp", "telnet",
+];
 
 pub struct ShellExecutor;
 
 impl ShellExecutor {
-    pub fn execute(command: &str) -> Result<String> {
-        let shell = if cfg!(target_os = "windows") {
-            "cmd"
+    /// Run `command` by injecting configured environment variables, then automatically routing
+    /// known interactive programs (editors, pagers, ssh, REPLs, ...) through a PTY so they get a
+    /// real terminal, and everything else through the existing fast line-buffered path. `cwd` is
+    /// the session's tracked working directory (see `ShellSession`) rather than aish's own
+    /// process cwd, so a prior `cd` persists.
+    ///
+    /// `command` must already have aliases expanded (see `expand_aliases`) — `App::run` expands
+    /// it before validation ever sees the command, so aliases can't be used to smuggle a pattern
+    /// past the whitelist/blocklist; expanding it again here would be too late to matter.
+    pub fn execute(
+        command: &str,
+        env: &BTreeMap<String, String>,
+        run_as_user: Option<&str>,
+        cwd: &Path,
+    ) -> Result<String> {
+        if Self::looks_interactive(command) {
+            // PTY child spawning goes through `portable_pty::CommandBuilder`, which doesn't
+            // expose a `pre_exec` hook to drop privileges post-fork/pre-exec like
+            // `std::process::Command` does, so run-as-user isn't supported on this path yet.
+            Self::execute_pty(command, env, cwd)
         } else {
-            "sh"
-        };
+            Self::execute_piped(command, env, run_as_user, cwd)
+        }
+    }
+
+    fn looks_interactive(command: &str) -> bool {
+        command
+            .split_whitespace()
+            .next()
+            .is_some_and(|first| INTERACTIVE_PROGRAMS.contains(&first))
+    }
+
+    /// The original fast path: piped stdout/stderr streamed line-by-line, stdin inherited. No
+    /// controlling terminal, so interactive/TUI programs will detect a non-tty and misbehave.
+    fn execute_piped(
+        command: &str,
+        env: &BTreeMap<String, String>,
+        run_as_user: Option<&str>,
+        cwd: &Path,
+    ) -> Result<String> {
+        #[cfg(unix)]
+        let identity = run_as_user.map(run_as::resolve_user).transpose()?;
+        #[cfg(not(unix))]
+        if run_as_user.is_some() {
+            anyhow::bail!("run_as_user is only supported on Unix");
+        }
+
+        #[cfg(unix)]
+        let shell = identity.as_ref().map(|i| i.shell.clone()).unwrap_or_else(|| "sh".to_string());
+        #[cfg(not(unix))]
+        let shell = "cmd".to_string();
 
         let flag = if cfg!(target_os = "windows") {
             "/C"
@@ -19,9 +84,15 @@ impl ShellExecutor {
             "-c"
         };
 
-        let mut child = Command::new(shell)
-            .arg(flag)
-            .arg(command)
+        let mut command_builder = Command::new(&shell);
+        command_builder.arg(flag).arg(command).envs(env).current_dir(cwd);
+
+        #[cfg(unix)]
+        if let Some(identity) = &identity {
+            apply_run_as_user(&mut command_builder, identity);
+        }
+
+        let mut child = command_builder
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .stdin(Stdio::inherit()) // Inherit stdin from parent process
@@ -87,4 +158,197 @@ impl ShellExecutor {
 
         Ok(output)
     }
+
+    /// Allocate a PTY, spawn `sh -c`/`cmd /C` as its child, and bridge the master end to our
+    /// real terminal: forward terminal size (polled, since that avoids pulling in a signal
+    /// crate just for `SIGWINCH`), set raw mode while the child runs, and copy bytes both ways.
+    /// Returns the child's output with ANSI escape sequences stripped, for the LLM transcript.
+    fn execute_pty(command: &str, env: &BTreeMap<String, String>, cwd: &Path) -> Result<String> {
+        let pty_system = native_pty_system();
+        let size = terminal_size();
+
+        let pair = pty_system
+            .openpty(PtySize {
+                rows: size.0,
+                cols: size.1,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .context("Failed to allocate PTY")?;
+
+        let shell = if cfg!(target_os = "windows") { "cmd" } else { "sh" };
+        let flag = if cfg!(target_os = "windows") { "/C" } else { "-c" };
+
+        let mut cmd = CommandBuilder::new(shell);
+        cmd.arg(flag);
+        cmd.arg(command);
+        cmd.env("TERM", std::env::var("TERM").unwrap_or_else(|_| "xterm-256color".to_string()));
+        for (key, value) in env {
+            cmd.env(key, value);
+        }
+        cmd.cwd(cwd);
+
+        let mut child = pair.slave.spawn_command(cmd).context("Failed to spawn PTY child")?;
+        // Only the child needs the slave end; dropping ours lets us see EOF on the master reader.
+        drop(pair.slave);
+
+        let mut reader = pair.master.try_clone_reader().context("Failed to clone PTY reader")?;
+        let mut writer = pair.master.take_writer().context("Failed to take PTY writer")?;
+
+        let raw_mode = RawModeGuard::enable();
+
+        let transcript_handle = thread::spawn(move || {
+            let mut transcript = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        io::stdout().write_all(&buf[..n]).ok();
+                        io::stdout().flush().ok();
+                        transcript.extend_from_slice(&buf[..n]);
+                    }
+                    Err(_) => break,
+                }
+            }
+            transcript
+        });
+
+        let stdin_done = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let stdin_done_writer = stdin_done.clone();
+        let _stdin_handle = thread::spawn(move || {
+            let mut stdin = io::stdin();
+            let mut buf = [0u8; 4096];
+            loop {
+                match stdin.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        if writer.write_all(&buf[..n]).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+            stdin_done_writer.store(true, std::sync::atomic::Ordering::Relaxed);
+        });
+
+        let master_resize = pair.master;
+        let resize_handle = thread::spawn(move || {
+            let mut last = size;
+            while !stdin_done.load(std::sync::atomic::Ordering::Relaxed) {
+                let current = terminal_size();
+                if current != last {
+                    let _ = master_resize.resize(PtySize {
+                        rows: current.0,
+                        cols: current.1,
+                        pixel_width: 0,
+                        pixel_height: 0,
+                    });
+                    last = current;
+                }
+                thread::sleep(Duration::from_millis(250));
+            }
+        });
+
+        let status = child.wait().context("Failed while waiting for PTY child")?;
+        drop(raw_mode);
+        // stdin_handle is left blocked in its read() call (stdin isn't ours to close) and is
+        // reaped when the process exits; only the resize thread is guaranteed to notice exit.
+        let _ = resize_handle.join();
+        let transcript = transcript_handle.join().unwrap_or_default();
+
+        let mut output = strip_ansi_escapes(&String::from_utf8_lossy(&transcript));
+        if !status.success() {
+            output.push_str(&format!("\n--- Process exited with code: {} ---\n", status.exit_code()));
+        }
+
+        Ok(output)
+    }
+}
+
+/// Set `HOME`/`USER`/`LOGNAME` from the resolved passwd entry and register a `pre_exec` hook
+/// that drops privileges to `identity` after `fork`, before `exec`, so only the spawned shell
+/// is affected and aish itself keeps running as whatever user launched it.
+#[cfg(unix)]
+fn apply_run_as_user(command: &mut Command, identity: &UserIdentity) {
+    command.env("HOME", &identity.home);
+    command.env("USER", &identity.username);
+    command.env("LOGNAME", &identity.username);
+    command.env("SHELL", &identity.shell);
+
+    let identity = UserIdentity {
+        username: identity.username.clone(),
+        uid: identity.uid,
+        gid: identity.gid,
+        groups: identity.groups.clone(),
+        home: identity.home.clone(),
+        shell: identity.shell.clone(),
+    };
+    unsafe {
+        command.pre_exec(move || run_as::drop_privileges(&identity));
+    }
+}
+
+/// Repeatedly replace the command's first token with its alias expansion until it stops
+/// matching an alias key or a cycle is detected (in which case the last expansion wins rather
+/// than looping forever). Called from `App::run` before a proposed shell command reaches
+/// `should_execute`/`execute_action`, so whitelist/blocklist validation always sees the fully
+/// expanded command rather than the pre-expansion text `ShellExecutor::execute` used to see.
+pub(crate) fn expand_aliases(command: &str, aliases: &BTreeMap<String, String>) -> String {
+    let mut current = command.to_string();
+    let mut visited = HashSet::new();
+
+    loop {
+        let Some((first, rest)) = current.split_once(char::is_whitespace) else {
+            return match aliases.get(&current) {
+                Some(expansion) if visited.insert(current.clone()) => expansion.clone(),
+                _ => current,
+            };
+        };
+
+        let Some(expansion) = aliases.get(first) else {
+            return current;
+        };
+        if !visited.insert(first.to_string()) {
+            return current;
+        }
+        current = format!("{} {}", expansion, rest);
+    }
+}
+
+/// Current controlling-terminal size as `(rows, cols)`, falling back to 24x80 when it can't be
+/// determined (e.g. stdout isn't a tty).
+fn terminal_size() -> (u16, u16) {
+    crossterm::terminal::size().map(|(cols, rows)| (rows, cols)).unwrap_or((24, 80))
+}
+
+/// Strips ANSI CSI/OSC escape sequences so the transcript fed back into the LLM loop is plain
+/// text instead of cursor-control noise.
+fn strip_ansi_escapes(input: &str) -> String {
+    let ansi_pattern = Regex::new(r"\x1b(\[[0-9;?]*[a-zA-Z]|\][^\x07\x1b]*(\x07|\x1b\\))").unwrap();
+    ansi_pattern.replace_all(input, "").to_string()
+}
+
+/// Puts the real terminal into raw mode for the PTY's lifetime, restoring the previous mode on
+/// drop so a crashed or early-returning child never leaves the user's shell in raw mode.
+struct RawModeGuard {
+    enabled: bool,
+}
+
+impl RawModeGuard {
+    fn enable() -> Self {
+        match crossterm::terminal::enable_raw_mode() {
+            Ok(()) => RawModeGuard { enabled: true },
+            Err(_) => RawModeGuard { enabled: false },
+        }
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        if self.enabled {
+            let _ = crossterm::terminal::disable_raw_mode();
+        }
+    }
 }
@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::security::SecurityValidator;
+
+/// What `ShellSession::intercept` recognized and already applied, so the caller knows not to
+/// spawn a subprocess for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intercepted {
+    Cd,
+    Assignment,
+}
+
+/// Tracks state that a real interactive shell would keep between commands — the working
+/// directory and any exported environment variables — across otherwise-isolated `execute_shell`
+/// tool calls, each of which still runs as its own subprocess (mirroring the `BTreeMap<String,
+/// String>` env plus tracked cwd the MOROS shell keeps in its own `Config`).
+pub struct ShellSession {
+    cwd: PathBuf,
+    env: BTreeMap<String, String>,
+}
+
+impl ShellSession {
+    pub fn new() -> Self {
+        Self {
+            cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            env: BTreeMap::new(),
+        }
+    }
+
+    pub fn cwd(&self) -> &PathBuf {
+        &self.cwd
+    }
+
+    pub fn env(&self) -> &BTreeMap<String, String> {
+        &self.env
+    }
+
+    /// Recognize `cd <dir>` and `export KEY=VAL` / bare `KEY=VAL` assignments, applying them to
+    /// the session instead of letting them reach `ShellExecutor`. Returns `None` for anything
+    /// else, meaning the caller should still spawn it as a real command.
+    pub fn intercept(
+        &mut self,
+        command: &str,
+        security: &SecurityValidator,
+    ) -> Result<Option<Intercepted>> {
+        if self.intercept_cd(command, security)? {
+            return Ok(Some(Intercepted::Cd));
+        }
+
+        if let Some(assignments) = parse_assignments(command) {
+            for (key, value) in assignments {
+                self.env.insert(key, value);
+            }
+            return Ok(Some(Intercepted::Assignment));
+        }
+
+        Ok(None)
+    }
+
+    fn intercept_cd(&mut self, command: &str, security: &SecurityValidator) -> Result<bool> {
+        let trimmed = command.trim();
+        let target = if trimmed == "cd" {
+            "~"
+        } else if let Some(rest) = trimmed.strip_prefix("cd ") {
+            rest.trim()
+        } else {
+            return Ok(false);
+        };
+
+        let expanded = self.expand_target(target);
+        security.validate_path(&expanded.to_string_lossy())?;
+
+        let canonical = std::fs::canonicalize(&expanded)
+            .with_context(|| format!("cd: no such directory: {}", target))?;
+        if !canonical.is_dir() {
+            anyhow::bail!("cd: not a directory: {}", target);
+        }
+
+        self.cwd = canonical;
+        Ok(true)
+    }
+
+    fn expand_target(&self, target: &str) -> PathBuf {
+        if target.is_empty() || target == "~" {
+            return dirs::home_dir().unwrap_or_else(|| self.cwd.clone());
+        }
+        if let Some(rest) = target.strip_prefix("~/") {
+            if let Some(home) = dirs::home_dir() {
+                return home.join(rest);
+            }
+        }
+
+        let candidate = PathBuf::from(target);
+        if candidate.is_absolute() {
+            candidate
+        } else {
+            self.cwd.join(candidate)
+        }
+    }
+}
+
+impl Default for ShellSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `command` is entirely one or more whitespace-separated `KEY=VALUE` tokens, optionally
+/// prefixed with `export `; anything else (a real command, possibly with a leading inline
+/// assignment like `FOO=bar somecmd`) returns `None` so it's executed normally instead.
+fn parse_assignments(command: &str) -> Option<Vec<(String, String)>> {
+    let body = command.trim().strip_prefix("export ").unwrap_or(command.trim());
+    if body.is_empty() {
+        return None;
+    }
+
+    let mut out = Vec::new();
+    for token in body.split_whitespace() {
+        let (key, value) = token.split_once('=')?;
+        if key.is_empty() || !key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        out.push((key.to_string(), value.to_string()));
+    }
+
+    (!out.is_empty()).then_some(out)
+}
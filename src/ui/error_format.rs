@@ -1,8 +1,152 @@
 use colored::*;
+use serde::Serialize;
 use serde_json::Value;
 
+use super::format::is_json;
+use super::plain::plain;
 use super::ui_utils::render_box;
 
+/// Print a single labeled field, as a bare `label: value` line in plain mode, or the existing
+/// bold-label-then-indented-colored-value block otherwise. Centralizing this keeps the plain
+/// invariant simple: every field shown in the fancy path still appears, one per line.
+fn print_field(label: &str, value: &str, color: Color) {
+    if plain().suppress_boxes {
+        println!("{}: {}", label, value);
+    } else {
+        println!("{}", format!("{}:", label).bright_white().bold());
+        println!("{}", format!("  {}", value).color(color));
+        println!();
+    }
+}
+
+/// Structured counterpart to `format_error`, reusing the exact fields `extract_error_from_json`
+/// already parses out of provider error bodies so callers don't have to scrape the rendered box.
+#[derive(Debug, Serialize, PartialEq)]
+struct ErrorJson {
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<Value>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    error_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<String>,
+}
+
+/// Render `error` on stdout, routing to the JSON path when `--format json` is active and to the
+/// existing ANSI-box path otherwise.
+pub fn print_error(error: &anyhow::Error) {
+    if is_json() {
+        println!(
+            "{}",
+            serde_json::to_string(&error_to_json(error)).unwrap_or_default()
+        );
+    } else {
+        format_error(error);
+    }
+}
+
+fn error_to_json(error: &anyhow::Error) -> ErrorJson {
+    let error_str = error.to_string();
+    let mut out = ErrorJson {
+        kind: "error",
+        status: None,
+        message: None,
+        code: None,
+        error_type: None,
+        provider: None,
+    };
+
+    if error_str.contains("Failed to parse API response") {
+        out.kind = "parse_error";
+        if let Some(colon_pos) = error_str.find(':') {
+            if let Some(response_body_pos) = error_str.find("Response body:") {
+                let parse_error = error_str[colon_pos + 1..response_body_pos].trim();
+                if !parse_error.is_empty() {
+                    out.message = Some(parse_error.to_string());
+                }
+
+                let response_body =
+                    error_str[response_body_pos + "Response body:".len()..].trim();
+                if let Some(json_start) = response_body.find('{') {
+                    if let Some(json_end) = response_body.rfind('}') {
+                        let json_str = &response_body[json_start..=json_end];
+                        if let Ok(json) = serde_json::from_str::<Value>(json_str) {
+                            fill_from_error_json(&json, &mut out);
+                        }
+                    }
+                }
+            } else {
+                let message = error_str[colon_pos + 1..].trim();
+                if !message.is_empty() {
+                    out.message = Some(message.to_string());
+                }
+            }
+        }
+        return out;
+    }
+
+    if error_str.contains("API error") {
+        out.kind = "api_error";
+
+        if let Some(start) = error_str.find('(') {
+            if let Some(end) = error_str[start..].find(')') {
+                out.status = error_str[start + 1..start + end].parse::<i64>().ok();
+            }
+        }
+
+        if let Some(json_start) = error_str.find('{') {
+            if let Some(json_end) = error_str.rfind('}') {
+                let json_str = &error_str[json_start..=json_end];
+                if let Ok(json) = serde_json::from_str::<Value>(json_str) {
+                    fill_from_error_json(&json, &mut out);
+                }
+            }
+        }
+
+        if out.message.is_none() {
+            if let Some(colon_pos) = error_str.find(':') {
+                let message = error_str[colon_pos + 1..].trim();
+                if !message.is_empty() && !message.chars().all(|c| c.is_whitespace() || c == '\n')
+                {
+                    out.message = Some(message.lines().next().unwrap_or(message).to_string());
+                }
+            }
+        }
+
+        return out;
+    }
+
+    out.message = Some(error_str);
+    out
+}
+
+fn fill_from_error_json(json: &Value, out: &mut ErrorJson) {
+    let Some(error_obj) = json.get("error") else {
+        return;
+    };
+
+    if let Some(message) = error_obj.get("message").and_then(|m| m.as_str()) {
+        out.message = Some(message.to_string());
+    }
+    if let Some(code) = error_obj.get("code") {
+        out.code = Some(code.clone());
+    }
+    if let Some(error_type) = error_obj.get("type").and_then(|t| t.as_str()) {
+        out.error_type = Some(error_type.to_string());
+    }
+    if let Some(provider) = error_obj
+        .get("metadata")
+        .and_then(|m| m.get("provider_name"))
+        .and_then(|p| p.as_str())
+    {
+        out.provider = Some(provider.to_string());
+    }
+}
+
 pub fn format_error(error: &anyhow::Error) {
     let error_str = error.to_string();
 
@@ -13,8 +157,7 @@ pub fn format_error(error: &anyhow::Error) {
         format_api_error(&error_str);
     } else {
         // Generic error formatting
-        println!("{}", "Description:".bright_white().bold());
-        println!("{}", format!("  {}", error_str).bright_red());
+        print_field("Description", &error_str, Color::Red);
     }
 }
 
@@ -28,9 +171,7 @@ fn format_api_error(error_str: &str) {
             if let Some(response_body_pos) = error_str.find("Response body:") {
                 let parse_error = error_str[colon_pos + 1..response_body_pos].trim();
                 if !parse_error.is_empty() {
-                    println!("{}", "Parse Error:".bright_white().bold());
-                    println!("{}", format!("  {}", parse_error).bright_yellow());
-                    println!();
+                    print_field("Parse Error", parse_error, Color::Yellow);
                 }
 
                 // Extract and parse the response body JSON
@@ -49,9 +190,7 @@ fn format_api_error(error_str: &str) {
                 if let Some(colon_pos) = error_str.find(':') {
                     let message = error_str[colon_pos + 1..].trim();
                     if !message.is_empty() {
-                        println!("{}", "Message:".bright_white().bold());
-                        println!("{}", format!("  {}", message).bright_red());
-                        println!();
+                        print_field("Message", message, Color::Red);
                     }
                 }
                 return;
@@ -72,9 +211,7 @@ fn format_api_error(error_str: &str) {
     };
 
     if let Some(status_code) = status {
-        println!("{}", "Status:".bright_white().bold());
-        println!("{}", format!("  {}", status_code).bright_yellow());
-        println!();
+        print_field("Status", status_code, Color::Yellow);
     }
 
     // Try to find and parse JSON in the error string
@@ -93,9 +230,7 @@ fn format_api_error(error_str: &str) {
             let message = error_str[colon_pos + 1..].trim();
             // Skip if message is empty or just whitespace
             if !message.is_empty() && !message.chars().all(|c| c.is_whitespace() || c == '\n') {
-                println!("{}", "Message:".bright_white().bold());
-                println!("{}", format!("  {}", message.lines().next().unwrap_or(message)).bright_red());
-                println!();
+                print_field("Message", message.lines().next().unwrap_or(message), Color::Red);
             }
         }
     }
@@ -105,42 +240,78 @@ fn extract_error_from_json(json: &Value, message_shown: &mut bool) {
     if let Some(error_obj) = json.get("error") {
         // Extract message
         if let Some(message) = error_obj.get("message").and_then(|m| m.as_str()) {
-            println!("{}", "Message:".bright_white().bold());
-            println!("{}", format!("  {}", message).bright_red());
-            println!();
+            print_field("Message", message, Color::Red);
             *message_shown = true;
         }
 
         // Extract code if available
         if let Some(code) = error_obj.get("code").and_then(|c| c.as_u64()) {
-            println!("{}", "Error Code:".bright_white().bold());
-            println!("{}", format!("  {}", code).bright_yellow());
-            println!();
+            print_field("Error Code", &code.to_string(), Color::Yellow);
         } else if let Some(code) = error_obj.get("code").and_then(|c| c.as_str()) {
-            println!("{}", "Error Code:".bright_white().bold());
-            println!("{}", format!("  {}", code).bright_yellow());
-            println!();
+            print_field("Error Code", code, Color::Yellow);
         }
 
         // Extract type if available
         if let Some(error_type) = error_obj.get("type").and_then(|t| t.as_str()) {
-            println!("{}", "Error Type:".bright_white().bold());
-            println!("{}", format!("  {}", error_type).bright_yellow());
-            println!();
+            print_field("Error Type", error_type, Color::Yellow);
         }
 
         // Extract metadata if available
         if let Some(metadata) = error_obj.get("metadata") {
             if let Some(provider) = metadata.get("provider_name").and_then(|p| p.as_str()) {
-                println!("{}", "Provider:".bright_white().bold());
-                println!("{}", format!("  {}", provider).bright_yellow());
-                println!();
+                print_field("Provider", provider, Color::Yellow);
             }
         }
     } else if !*message_shown {
         // Fallback: show the whole JSON if no error object found
-        println!("{}", "Details:".bright_white().bold());
-        println!("{}", format!("  {}", json).bright_red());
-        println!();
+        print_field("Details", &json.to_string(), Color::Red);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_has_parse_error_kind_and_nested_fields() {
+        let error = anyhow::anyhow!(
+            "Failed to parse API response: invalid type: null. Response body: {}",
+            r#"{"error":{"message":"unexpected null","code":"bad_response","type":"invalid_response_error"}}"#
+        );
+
+        let json = error_to_json(&error);
+        assert_eq!(json.kind, "parse_error");
+        assert_eq!(json.message.as_deref(), Some("unexpected null"));
+        assert_eq!(json.code, Some(Value::String("bad_response".to_string())));
+        assert_eq!(json.error_type.as_deref(), Some("invalid_response_error"));
+    }
+
+    #[test]
+    fn api_error_has_status_and_provider() {
+        let error = anyhow::anyhow!(
+            "API error (429): {}",
+            r#"{"error":{"message":"too many requests","code":"rate_limited","type":"rate_limit_error","metadata":{"provider_name":"openai"}}}"#
+        );
+
+        let json = error_to_json(&error);
+        assert_eq!(json.kind, "api_error");
+        assert_eq!(json.status, Some(429));
+        assert_eq!(json.message.as_deref(), Some("too many requests"));
+        assert_eq!(json.code, Some(Value::String("rate_limited".to_string())));
+        assert_eq!(json.error_type.as_deref(), Some("rate_limit_error"));
+        assert_eq!(json.provider.as_deref(), Some("openai"));
+    }
+
+    #[test]
+    fn generic_error_falls_back_to_message_only() {
+        let error = anyhow::anyhow!("disk is full");
+
+        let json = error_to_json(&error);
+        assert_eq!(json.kind, "error");
+        assert_eq!(json.status, None);
+        assert_eq!(json.message.as_deref(), Some("disk is full"));
+        assert_eq!(json.code, None);
+        assert_eq!(json.error_type, None);
+        assert_eq!(json.provider, None);
     }
 }
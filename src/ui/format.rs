@@ -0,0 +1,26 @@
+use std::sync::OnceLock;
+
+/// Output format for config/result/error rendering, selected with `--format`. Mirrors how
+/// compilers expose both a human `rendered` diagnostic and a structured one: the `Pretty` path
+/// is the existing ANSI-box rendering (further affected by plain mode), `Json` is a parallel,
+/// stable machine-readable path for scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Pretty,
+    Json,
+}
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Make the selected `--format` available to every renderer via `is_json()`. Must be called
+/// once, early in `main`, before any output is produced.
+pub fn init(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+pub fn is_json() -> bool {
+    matches!(
+        FORMAT.get().copied().unwrap_or(OutputFormat::Pretty),
+        OutputFormat::Json
+    )
+}
@@ -1,6 +1,15 @@
 use termimad::MadSkin;
 
+use super::plain::plain;
+
 pub fn render_markdown(text: &str) {
+    if plain().suppress_color {
+        // termimad renders through its own ANSI skin rather than the `colored` crate, so the
+        // global color override doesn't reach it — fall back to the raw text in plain mode.
+        println!("{}", text);
+        return;
+    }
+
     let mut skin = MadSkin::default();
 
     // Customize colors for better appearance
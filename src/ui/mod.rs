@@ -1,7 +1,11 @@
 pub mod error_format;
+pub mod format;
 pub mod markdown;
+pub mod plain;
 mod ui_utils;
 
-pub use error_format::format_error;
+pub use error_format::{format_error, print_error};
+pub use format::{init as init_format, OutputFormat};
 pub use markdown::render_markdown;
+pub use plain::{init as init_plain, plain as plain_info, PlainInfo};
 pub use ui_utils::*;
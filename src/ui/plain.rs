@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Mirrors Mercurial's `HGPLAIN`: one source of truth for whether output should be scriptable
+/// (no ANSI color, no box-drawing) that every renderer consults instead of each one re-deriving
+/// it from env vars.
+#[derive(Debug, Clone, Copy)]
+pub struct PlainInfo {
+    pub suppress_color: bool,
+    pub suppress_boxes: bool,
+}
+
+static PLAIN: OnceLock<PlainInfo> = OnceLock::new();
+
+/// Compute plain mode from `--plain` plus `NO_COLOR`/`AISH_PLAIN`/`AISH_PLAINEXCEPT` and make it
+/// available to every renderer via `plain()`. Must be called once, early in `main`, before any
+/// output is produced.
+pub fn init(cli_plain: bool) {
+    let no_color = std::env::var_os("NO_COLOR").is_some();
+    let aish_plain = std::env::var("AISH_PLAIN").is_ok_and(|v| v != "0" && !v.is_empty());
+    let except: HashSet<String> = std::env::var("AISH_PLAINEXCEPT")
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let plain_mode = cli_plain || aish_plain;
+    let info = PlainInfo {
+        // NO_COLOR (per no-color.org) only ever strips color; AISH_PLAIN/--plain additionally
+        // drops box-drawing, each independently overridable via AISH_PLAINEXCEPT.
+        suppress_color: (plain_mode || no_color) && !except.contains("color"),
+        suppress_boxes: plain_mode && !except.contains("boxes"),
+    };
+
+    if info.suppress_color {
+        colored::control::set_override(false);
+    }
+
+    let _ = PLAIN.set(info);
+}
+
+pub fn plain() -> PlainInfo {
+    PLAIN.get().copied().unwrap_or(PlainInfo { suppress_color: false, suppress_boxes: false })
+}
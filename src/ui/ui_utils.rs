@@ -1,5 +1,7 @@
 use colored::*;
 
+use super::plain::plain;
+
 const BOX_WIDTH: usize = 56;
 const BOX_CONTENT_WIDTH: usize = BOX_WIDTH - 2;
 
@@ -16,6 +18,10 @@ const SECTION_HORIZONTAL: &str = "─";
 const SECTION_VERTICAL: &str = "│";
 
 pub fn render_box(title: &str, color: Color) {
+    if plain().suppress_boxes {
+        println!("{}", title);
+        return;
+    }
     println!();
     println!("{}", format_box_top(color));
     println!("{}", format_box_title(title, color));
@@ -25,17 +31,28 @@ pub fn render_box(title: &str, color: Color) {
 
 /// Render a section header with custom text and color
 pub fn render_section(title: &str, color: Color) {
+    if plain().suppress_boxes {
+        println!("{}:", title);
+        return;
+    }
     println!("{}", format_section_header(title, color));
 }
 
 /// Render a section footer
 pub fn render_section_footer() {
+    if plain().suppress_boxes {
+        return;
+    }
     println!("{}", format_section_footer());
     println!();
 }
 
 /// Render a line inside a section with key-value pair
 pub fn render_section_line(key: &str, value: ColoredString) {
+    if plain().suppress_boxes {
+        println!("{}: {}", key, value);
+        return;
+    }
     println!(
         "{} {} {}",
         SECTION_VERTICAL.bright_black(),
@@ -46,6 +63,10 @@ pub fn render_section_line(key: &str, value: ColoredString) {
 
 /// Render a list item inside a section
 pub fn render_section_item(item: ColoredString) {
+    if plain().suppress_boxes {
+        println!("{}", item);
+        return;
+    }
     println!("{} {}", SECTION_VERTICAL.bright_black(), item);
 }
 
@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use notify::event::{CreateKind, ModifyKind, RemoveKind};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use crate::security::SecurityValidator;
+
+/// Bursts of filesystem events within this window (an editor's save can fire several
+/// create/modify/rename events for one logical change) collapse into a single batch.
+pub const DEBOUNCE: Duration = Duration::from_millis(400);
+
+/// How a changed path was reported by `notify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Modified => "modified",
+            ChangeKind::Removed => "removed",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub kind: ChangeKind,
+}
+
+/// Start watching `root` recursively. Returns the watcher (which must be kept alive for as long
+/// as events are wanted — dropping it stops the watch) and the receiving end of its event
+/// channel.
+pub fn spawn_watcher(root: &Path) -> Result<(RecommendedWatcher, Receiver<notify::Result<Event>>)> {
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to create file watcher")?;
+    watcher
+        .watch(root, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", root.display()))?;
+    Ok((watcher, rx))
+}
+
+/// Translate a raw `notify` event into the changed paths it covers, dropping anything that
+/// matches `security`'s ignore patterns (the same list `.aishignore`/config feeds `validate_path`)
+/// so build artifacts and ignored files don't trigger a loop.
+pub fn classify_event(event: &Event, security: &SecurityValidator) -> Vec<FileChange> {
+    let kind = match event.kind {
+        EventKind::Create(CreateKind::File) | EventKind::Create(CreateKind::Any) => ChangeKind::Created,
+        EventKind::Modify(ModifyKind::Data(_)) | EventKind::Modify(ModifyKind::Any) => ChangeKind::Modified,
+        EventKind::Remove(RemoveKind::File) | EventKind::Remove(RemoveKind::Any) => ChangeKind::Removed,
+        _ => return Vec::new(),
+    };
+
+    event
+        .paths
+        .iter()
+        .filter(|path| !is_ignored(path, security))
+        .map(|path| FileChange {
+            path: path.clone(),
+            kind,
+        })
+        .collect()
+}
+
+fn is_ignored(path: &Path, security: &SecurityValidator) -> bool {
+    let path_str = path.to_string_lossy();
+    security
+        .ignore_patterns()
+        .iter()
+        .any(|pattern| pattern.is_match(&path_str))
+}
+
+/// Keep only the most recent event per path in a batch — a single save can otherwise report
+/// modify+modify+modify for the same file.
+pub fn dedupe(changes: Vec<FileChange>) -> Vec<FileChange> {
+    let mut seen = HashSet::new();
+    let mut out: Vec<FileChange> = Vec::new();
+    for change in changes.into_iter().rev() {
+        if seen.insert(change.path.clone()) {
+            out.push(change);
+        }
+    }
+    out.reverse();
+    out
+}
+
+/// Render a batch of changes as the synthetic user message body fed into the chat loop.
+pub fn describe_changes(changes: &[FileChange]) -> String {
+    changes
+        .iter()
+        .map(|change| format!("- {} ({})", change.path.display(), change.kind))
+        .collect::<Vec<_>>()
+        .join("\n")
+}